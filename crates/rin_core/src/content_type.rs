@@ -0,0 +1,161 @@
+use crate::error::RinError;
+use http::HeaderValue;
+
+/// 解析后的 `Content-Type` 头。
+///
+/// 类型、子类型和参数名统一为小写；参数值保持原样，带引号的值会去掉引号并处理转义。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentType {
+    media_type: String,
+    subtype: String,
+    params: Vec<(String, String)>,
+}
+
+impl ContentType {
+    /// 解析 `type/subtype; name=value; ...` 形式的头部值。
+    ///
+    /// # Errors
+    /// 头部值不是可见 ASCII、缺少 `/`、类型为空或参数格式不正确时返回 `RinError::BadRequest`。
+    pub fn parse(value: &HeaderValue) -> Result<ContentType, RinError> {
+        let value = value
+            .to_str()
+            .map_err(|_| invalid("Content-Type is not visible ASCII"))?;
+        let (essence, mut rest) = value.split_once(';').unwrap_or((value, ""));
+
+        let (media_type, subtype) = essence
+            .trim()
+            .split_once('/')
+            .ok_or_else(|| invalid("Content-Type is missing a subtype"))?;
+        if !is_token(media_type) || !is_token(subtype) {
+            return Err(invalid("Content-Type has an invalid media type"));
+        }
+
+        let mut params = Vec::new();
+        loop {
+            rest = rest.trim_start_matches([' ', '\t', ';']);
+            if rest.is_empty() {
+                break;
+            }
+            let (name, after_name) = rest
+                .split_once('=')
+                .ok_or_else(|| invalid("Content-Type parameter is missing a value"))?;
+            let name = name.trim();
+            if !is_token(name) {
+                return Err(invalid("Content-Type has an invalid parameter name"));
+            }
+
+            let after_name = after_name.trim_start();
+            let (param_value, remaining) = if let Some(quoted) = after_name.strip_prefix('"') {
+                parse_quoted(quoted)?
+            } else {
+                let (raw, remaining) = after_name.split_once(';').unwrap_or((after_name, ""));
+                (raw.trim_end().to_string(), remaining)
+            };
+            params.push((name.to_ascii_lowercase(), param_value));
+            rest = remaining.trim_start();
+            if !rest.is_empty() && !rest.starts_with(';') {
+                return Err(invalid("Content-Type parameters must be separated by ';'"));
+            }
+        }
+
+        Ok(ContentType {
+            media_type: media_type.to_ascii_lowercase(),
+            subtype: subtype.to_ascii_lowercase(),
+            params,
+        })
+    }
+
+    /// 主类型，例如 `text/html` 中的 `text`。
+    pub fn media_type(&self) -> &str {
+        &self.media_type
+    }
+
+    /// 子类型，例如 `text/html` 中的 `html`。
+    pub fn subtype(&self) -> &str {
+        &self.subtype
+    }
+
+    /// 按名称（不区分大小写）获取参数值，例如 `get_param("charset")`。
+    pub fn get_param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// 是否为给定的 `type/subtype`（不区分大小写，忽略参数）。
+    pub fn is(&self, essence: &str) -> bool {
+        essence.split_once('/').is_some_and(|(ty, subtype)| {
+            ty.eq_ignore_ascii_case(&self.media_type) && subtype.eq_ignore_ascii_case(&self.subtype)
+        })
+    }
+}
+
+// 解析引号内的内容（起始引号已去掉），返回解转义后的值和结束引号之后的剩余部分
+fn parse_quoted(input: &str) -> Result<(String, &str), RinError> {
+    let mut value = String::new();
+    let mut chars = input.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((value, &input[i + 1..])),
+            '\\' => match chars.next() {
+                Some((_, escaped)) => value.push(escaped),
+                None => break,
+            },
+            c => value.push(c),
+        }
+    }
+    Err(invalid("Content-Type has an unterminated quoted string"))
+}
+
+// RFC 7230 中的 token 字符
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
+
+fn invalid(message: &str) -> RinError {
+    RinError::BadRequest(message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &'static str) -> Result<ContentType, RinError> {
+        ContentType::parse(&HeaderValue::from_static(value))
+    }
+
+    #[test]
+    fn parses_type_and_params() {
+        let content_type = parse(r#"Text/HTML; Charset="utf-8"; q="a\"b;c"  "#).unwrap();
+        assert_eq!(content_type.media_type(), "text");
+        assert_eq!(content_type.subtype(), "html");
+        assert_eq!(content_type.get_param("charset"), Some("utf-8"));
+        assert_eq!(content_type.get_param("q"), Some(r#"a"b;c"#));
+        assert!(content_type.is("text/html"));
+
+        let content_type = parse("application/json;charset=UTF-8 ").unwrap();
+        assert!(content_type.is("application/json"));
+        assert_eq!(content_type.get_param("charset"), Some("UTF-8"));
+        assert_eq!(content_type.get_param("boundary"), None);
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        for value in [
+            "text",
+            "/html",
+            "text/html; charset",
+            r#"text/html; a="b"#,
+            "a b/c",
+        ] {
+            assert!(
+                matches!(parse(value), Err(RinError::BadRequest(_))),
+                "{}",
+                value
+            );
+        }
+    }
+}
@@ -1,7 +1,12 @@
+use crate::cookie::Cookie;
+use crate::error::RinError;
 use crate::request::{FormCache, QueryCache, Request};
-use crate::response::Response;
+use crate::response::{IntoResponse, Response};
+use crate::shutdown::ShutdownSignal;
 use std::any::{Any, TypeId}; // 用于存储用户自定义数据
 use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 // 用于辅助 Any 到 Box<Any> 的转换，如果需要的话
 
 /// 请求处理的上下文。
@@ -12,8 +17,6 @@ pub struct Context {
     response: Response,
     // 路径参数，例如 /users/:id 中的 id
     params: HashMap<String, String>,
-    // 当前处理函数的索引，跟踪中间件执行进度
-    index: usize,
 
     // 用于缓存解析后的查询参数。
     query_cache: QueryCache,
@@ -29,13 +32,13 @@ impl Context {
     /// 创建一个新的上下文。
     /// 通常由服务器在接收到请求时创建。
     pub fn new(request: Request, response: Response) -> Self {
+        let query_cache = request.query_str().map(QueryCache::parse).unwrap_or_default();
         Context {
             request,
             response,
             params: HashMap::new(),
             data: HashMap::new(),
-            index: 0,
-            query_cache: QueryCache::new(),
+            query_cache,
             form_cache: FormCache::new(),
             // error: None,
         }
@@ -45,6 +48,19 @@ impl Context {
     pub fn param(&self, name: &str) -> Option<&str> {
         self.params.get(name).map(|s| s.as_str())
     }
+
+    /// 获取路由模式中声明的路径参数。
+    ///
+    /// 路由匹配成功时这些参数必然存在，缺失说明路由与处理函数不一致，属于编程错误。
+    ///
+    /// # Errors
+    /// 参数缺失时返回 `RinError::Internal`。
+    pub fn param_required(&self, name: &str) -> Result<&str, RinError> {
+        self.param(name).ok_or_else(|| {
+            log::error!("Route parameter '{}' is missing", name);
+            RinError::Internal(format!("Missing route parameter '{}'", name))
+        })
+    }
     pub fn query(&self, name: &str) -> Option<&str> {
         self.query_cache.get(name)
     }
@@ -56,11 +72,26 @@ impl Context {
        unimplemented!() 
     }
     pub fn post_form(&self, name: &str) -> Option<&str> {
-        unimplemented!()
+        self.form_cache.get_field(name)
+    }
+
+    /// 获取已解析的表单数据，包括上传的文件。
+    pub fn form(&self) -> &FormCache {
+        &self.form_cache
+    }
+
+    /// 获取表单数据的可变引用，供表单解析器写入字段和文件。
+    pub fn form_mut(&mut self) -> &mut FormCache {
+        &mut self.form_cache
     }
     
     
 
+    /// 获取当前请求。
+    pub fn request(&self) -> &Request {
+        &self.request
+    }
+
     /// 获取请求的 Method。
     pub fn method(&self) -> &http::Method {
         &self.request.method
@@ -76,6 +107,27 @@ impl Context {
         &self.request.headers
     }
 
+    /// 获取请求头的文本值；不存在或不是可见 ASCII 时返回 `None`。
+    ///
+    /// 同名头有多个值时返回第一个。
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.request.headers.get(name)?.to_str().ok()
+    }
+
+    /// 获取请求头并解析为 `T`，例如 `ctx.header_parsed::<u64>("content-length")`。
+    ///
+    /// 头不存在时返回 `None`；存在但无法解析时返回 `RinError::BadRequest`。
+    pub fn header_parsed<T: FromStr>(&self, name: &str) -> Option<Result<T, RinError>> {
+        let value = self.request.headers.get(name)?;
+        Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .ok_or_else(|| RinError::BadRequest(format!("Invalid value for header '{}'", name))),
+        )
+    }
+
     /// 获取请求体的原始字节数据。
     pub fn body_bytes(&self) -> &bytes::Bytes {
         &self.request.body_bytes
@@ -91,11 +143,57 @@ impl Context {
         self.response.set_body(body);
     }
 
+    /// 获取当前构建中的响应。
+    pub fn response(&self) -> &Response {
+        &self.response
+    }
+
+    /// 向响应体追加一段数据，适合逐段构建中等大小的响应。
+    ///
+    /// 首次写入时如果尚未设置 `Content-Type`，则默认设置为 `text/plain; charset=utf-8`。
+    /// 连续写入会复用同一块缓冲区并按需扩容，总开销与响应体长度成线性关系。
+    pub fn write(&mut self, chunk: impl Into<bytes::Bytes>) {
+        if self.response.body.is_empty()
+            && !self.response.headers.contains_key(http::header::CONTENT_TYPE)
+        {
+            self.response.headers_mut().insert(
+                http::header::CONTENT_TYPE,
+                crate::response::utf8_content_type("text/plain"),
+            );
+        }
+
+        let chunk = chunk.into();
+        if self.response.body.is_empty() {
+            self.response.set_body(chunk);
+            return;
+        }
+        // 响应体未被共享时直接取回底层缓冲区原地追加，避免每次写入都复制整个响应体
+        let body = std::mem::take(&mut self.response.body);
+        let mut buf = body
+            .try_into_mut()
+            .unwrap_or_else(|shared| bytes::BytesMut::from(&shared[..]));
+        buf.extend_from_slice(&chunk);
+        self.response.set_body(buf.freeze());
+    }
+
     /// 获取响应头可变引用。
     pub fn headers_mut(&mut self) -> &mut http::HeaderMap {
         self.response.headers_mut()
     }
 
+    /// 追加响应头，保留同名的已有值（例如多个 `Set-Cookie`）。
+    pub fn append_header(&mut self, key: http::header::HeaderName, value: http::HeaderValue) {
+        self.response.append_header(key, value);
+    }
+
+    /// 在响应中追加一个 `Set-Cookie` 头，已设置的 Cookie 会被保留。
+    ///
+    /// # Errors
+    /// Cookie 无法转换为合法的头部值时返回 `RinError::Internal`，响应保持不变。
+    pub fn set_cookie(&mut self, cookie: Cookie) -> Result<(), RinError> {
+        self.response.set_cookie(cookie)
+    }
+
     /// 将数据存储到上下文中，供后续处理函数或中间件使用。
     /// `T` 必须是 `'static` 并且 `Send + Sync`。
     pub fn set<T: Any + Send + Sync + 'static>(&mut self, value: T) {
@@ -116,6 +214,182 @@ impl Context {
             .and_then(|boxed_value| boxed_value.downcast_mut::<T>())
     }
 
+    /// 注入服务器的停机信号，通常由服务器在创建上下文时调用。
+    pub fn set_shutdown_signal(&mut self, signal: ShutdownSignal) {
+        self.set(signal);
+    }
+
+    /// 服务器是否正在优雅停机。
+    ///
+    /// 长时间运行的处理函数（SSE、流式响应）应定期检查并尽快结束。
+    /// 未注入停机信号时始终返回 `false`。
+    pub fn is_shutting_down(&self) -> bool {
+        self.get::<ShutdownSignal>().is_some_and(ShutdownSignal::is_triggered)
+    }
+
+    /// 找到时返回对应的响应，否则返回 `RinError::NotFound`。
+    ///
+    /// 适用于处理函数中常见的“查到就返回，查不到就 404”的场景。
+    pub fn found<T: IntoResponse>(&self, opt: Option<T>) -> Result<Response, RinError> {
+        opt.map(IntoResponse::into_response).ok_or(RinError::NotFound)
+    }
+
+    /// 根据 `Accept-Language` 头从 `supported` 中选出客户端最偏好的语言。
+    ///
+    /// 语言范围按不区分大小写的前缀匹配：`en` 可匹配 `en-US`，
+    /// 而 `fr-CH` 在没有更精确的候选时回退匹配 `fr`。同一语言命中多个范围时取最具体的那个，
+    /// `q=0` 表示不可接受。权重相同时按 `supported` 的顺序选择。
+    /// 请求没有 `Accept-Language` 时返回 `supported` 的第一项。
+    pub fn preferred_language<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        self.negotiate(http::header::ACCEPT_LANGUAGE, supported, language_match_rank)
+    }
+
+    /// 根据 `Accept` 头从 `supported` 中选出客户端最偏好的媒体类型。
+    ///
+    /// `type/subtype` 精确匹配优先于 `type/*`，其次是 `*/*`；`q=0` 表示不可接受。
+    /// 权重相同时按 `supported` 的顺序选择，请求没有 `Accept` 时返回 `supported` 的第一项。
+    pub fn preferred_media_type<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        self.negotiate(http::header::ACCEPT, supported, media_match_rank)
+    }
+
+    // 按带权重的请求头列表在 `supported` 中选择，`rank` 越大表示范围越具体
+    fn negotiate<'a, R: Ord>(
+        &self,
+        header: http::header::HeaderName,
+        supported: &[&'a str],
+        rank: impl Fn(&str, &str) -> Option<R>,
+    ) -> Option<&'a str> {
+        let ranges: Vec<_> = self
+            .request
+            .list_values(header)
+            .map(crate::request::split_quality)
+            .collect();
+        if ranges.is_empty() {
+            return supported.first().copied();
+        }
+
+        let mut best: Option<(&'a str, f32)> = None;
+        for &candidate in supported {
+            let matched = ranges
+                .iter()
+                .filter_map(|&(range, q)| rank(range, candidate).map(|rank| (rank, q)))
+                .max_by(|(a, _), (b, _)| a.cmp(b));
+            if let Some((_, q)) = matched
+                && q > 0.0
+                && best.is_none_or(|(_, best_q)| q > best_q)
+            {
+                best = Some((candidate, q));
+            }
+        }
+        best.map(|(candidate, _)| candidate)
+    }
+
+    /// 检查 `If-Match` 前置条件，用于 PUT/PATCH 等写操作的乐观锁。
+    ///
+    /// `etag` 是资源当前的实体标签（含引号，例如 `"v2"`）。`If-Match` 使用强比较，
+    /// 弱标签（`W/"..."`）永远不匹配；`*` 匹配任何存在的资源。
+    /// 返回 `true` 表示前置条件失败，此时已设置 412 Precondition Failed，处理函数应直接返回。
+    /// 请求未携带 `If-Match` 时返回 `false`。
+    pub fn precondition_failed_unless_match(&mut self, etag: &str) -> bool {
+        let mut tags = self
+            .request
+            .headers
+            .get_all(http::header::IF_MATCH)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(entity_tags)
+            .peekable();
+        if tags.peek().is_none() {
+            return false;
+        }
+        let weak = etag.starts_with("W/");
+        let matched = tags.any(|tag| tag == "*" || (!weak && tag == etag));
+        if !matched {
+            self.response.set_status(http::StatusCode::PRECONDITION_FAILED);
+        }
+        !matched
+    }
+
+    /// 检查 `If-Unmodified-Since` 前置条件。
+    ///
+    /// 资源在请求给出的时间之后被修改过时设置 412 并返回 `true`。
+    /// 按 RFC 7232，请求同时携带 `If-Match` 时忽略此头；日期无法解析时同样忽略。
+    pub fn precondition_failed_unless_unmodified_since(&mut self, last_modified: SystemTime) -> bool {
+        if self.request.headers.contains_key(http::header::IF_MATCH) {
+            return false;
+        }
+        let since = match self
+            .request
+            .headers
+            .get(http::header::IF_UNMODIFIED_SINCE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(crate::date::parse_httpdate)
+        {
+            Some(since) => since,
+            None => return false,
+        };
+
+        // HTTP 日期只精确到秒
+        let last_modified_secs = last_modified
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let modified = UNIX_EPOCH + Duration::from_secs(last_modified_secs) > since;
+        if modified {
+            self.response.set_status(http::StatusCode::PRECONDITION_FAILED);
+        }
+        modified
+    }
+
+    /// 将请求头名称加入响应的 `Vary` 列表。
+    ///
+    /// 已有的 `Vary` 值会原样保留并合并为一行，名称不区分大小写去重；
+    /// 已经是 `Vary: *` 时不做改动。
+    pub fn add_vary(&mut self, header_name: http::header::HeaderName) {
+        let mut names: Vec<&[u8]> = self
+            .response
+            .headers
+            .get_all(http::header::VARY)
+            .iter()
+            .flat_map(|line| line.as_bytes().split(|&b| b == b','))
+            .map(<[u8]>::trim_ascii)
+            .filter(|name| !name.is_empty())
+            .collect();
+        if names.contains(&&b"*"[..]) {
+            return;
+        }
+        let new_name = header_name.as_str().as_bytes();
+        if !names.iter().any(|name| name.eq_ignore_ascii_case(new_name)) {
+            names.push(new_name);
+        }
+
+        // 由已有的合法头部值和头部名拼接而成，结果总是合法的头部值
+        if let Ok(value) = http::HeaderValue::from_bytes(&names.join(&b", "[..])) {
+            self.response.headers_mut().insert(http::header::VARY, value);
+        }
+    }
+
+    /// 设置 `Expires` 响应头，以 IMF-fixdate 格式表示过期时间。
+    pub fn set_expires(&mut self, at: SystemTime) {
+        let value = http::HeaderValue::from_str(&crate::date::httpdate(at))
+            .expect("HTTP 日期应当是合法的头部值");
+        self.response.headers_mut().insert(http::header::EXPIRES, value);
+    }
+
+    /// 同时设置 `Cache-Control: max-age` 与对应的 `Expires`，
+    /// 使只认识 `Expires` 的旧缓存也能得到一致的过期时间。
+    ///
+    /// 过期时间超出 HTTP 日期能表示的范围时，`Expires` 取 `Fri, 31 Dec 9999 23:59:59 GMT`。
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        let value = http::HeaderValue::from_str(&format!("max-age={}", max_age.as_secs()))
+            .expect("max-age 应当是合法的头部值");
+        self.response.headers_mut().insert(http::header::CACHE_CONTROL, value);
+        let latest = UNIX_EPOCH + Duration::from_secs(crate::date::MAX_HTTPDATE_SECS);
+        let expires = SystemTime::now()
+            .checked_add(max_age)
+            .map_or(latest, |at| at.min(latest));
+        self.set_expires(expires);
+    }
+
     // TODO: 实现更多 Gin-like 的便捷方法，例如：
     // - HTML 渲染方法 (需要渲染器集成)
     // - 重定向方法
@@ -123,3 +397,394 @@ impl Context {
     // - 获取客户端 IP
     // - 获取请求 ID (如果使用)
 }
+
+// 语言范围与语言标签的匹配程度，越大越具体；不匹配时返回 None
+fn language_match_rank(range: &str, tag: &str) -> Option<(u8, usize)> {
+    let is_prefix = |short: &str, long: &str| {
+        long.len() > short.len()
+            && long.as_bytes()[short.len()] == b'-'
+            && long[..short.len()].eq_ignore_ascii_case(short)
+    };
+    if range.eq_ignore_ascii_case(tag) {
+        Some((3, range.len()))
+    } else if is_prefix(range, tag) {
+        Some((2, range.len()))
+    } else if is_prefix(tag, range) {
+        Some((1, range.len()))
+    } else if range == "*" {
+        Some((0, 0))
+    } else {
+        None
+    }
+}
+
+// 媒体范围与媒体类型的匹配程度：精确匹配 > `type/*` > `*/*`；不匹配时返回 None
+fn media_match_rank(range: &str, media_type: &str) -> Option<u8> {
+    let (range_type, range_subtype) = range.split_once('/')?;
+    let (ty, subtype) = media_type.split_once('/')?;
+    if !range_type.eq_ignore_ascii_case(ty) {
+        return (range_type == "*" && range_subtype == "*").then_some(0);
+    }
+    if range_subtype.eq_ignore_ascii_case(subtype) {
+        Some(2)
+    } else if range_subtype == "*" {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+// 将 `If-Match` 等头部的一行拆分为实体标签；引号内允许出现逗号，不能直接按逗号切分
+fn entity_tags(line: &str) -> impl Iterator<Item = &str> {
+    let mut rest = line;
+    std::iter::from_fn(move || {
+        rest = rest.trim_start_matches(|c: char| c == ',' || c.is_ascii_whitespace());
+        if rest.is_empty() {
+            return None;
+        }
+        let mut in_quotes = false;
+        let end = rest
+            .char_indices()
+            .find(|&(_, c)| {
+                if c == '"' {
+                    in_quotes = !in_quotes;
+                }
+                c == ',' && !in_quotes
+            })
+            .map_or(rest.len(), |(i, _)| i);
+        let (tag, tail) = rest.split_at(end);
+        rest = tail;
+        Some(tag.trim_end())
+    })
+}
+
+/// 单元测试辅助：快速构造上下文。
+///
+/// 在本 crate 的测试中自动可用，其他 crate 需要启用 `testing` feature。
+#[cfg(any(test, feature = "testing"))]
+impl Context {
+    /// 使用给定的方法和 URI 构造上下文，其余字段取默认值。
+    ///
+    /// # Panics
+    /// `uri` 不是合法的 URI 时 panic。
+    pub fn test(method: http::Method, uri: &str) -> Self {
+        let uri = uri.parse::<http::Uri>().expect("测试 URI 应当合法");
+        let request = Request::new(method, uri, http::HeaderMap::new(), bytes::Bytes::new());
+        Context::new(request, Response::new())
+    }
+
+    /// 追加一个请求头。
+    ///
+    /// # Panics
+    /// 头部名或值不合法时 panic。
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        let name = http::header::HeaderName::from_bytes(name.as_bytes()).expect("测试头部名应当合法");
+        let value = http::HeaderValue::from_str(value).expect("测试头部值应当合法");
+        self.request.headers.append(name, value);
+        self
+    }
+
+    /// 设置请求体。
+    pub fn with_body(mut self, body: impl Into<bytes::Bytes>) -> Self {
+        self.request.body_bytes = body.into();
+        self
+    }
+
+    /// 设置路径参数，模拟路由匹配的结果。
+    pub fn with_param(mut self, name: &str, value: &str) -> Self {
+        self.params.insert(name.to_string(), value.to_string());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::Handler;
+    use bytes::Bytes;
+    use http::{Method, StatusCode};
+
+    async fn greet(ctx: Context) -> Result<String, RinError> {
+        let name = ctx.param("name").ok_or(RinError::NotFound)?;
+        let greeting = ctx.query("greeting").unwrap_or("Hello");
+        let agent = ctx.headers().get("user-agent").and_then(|v| v.to_str().ok()).unwrap_or("?");
+        Ok(format!("{}, {} ({}, {} bytes)", greeting, name, agent, ctx.body_bytes().len()))
+    }
+
+    #[test]
+    fn preferred_language_honors_weights_and_ranges() {
+        let ctx = Context::test(Method::GET, "/")
+            .with_header("Accept-Language", "fr-CH, fr;q=0.9, en;q=0.8");
+        assert_eq!(ctx.preferred_language(&["en", "fr"]), Some("fr"));
+
+        let ctx = Context::test(Method::GET, "/")
+            .with_header("Accept-Language", "en;q=0.5, de;q=0.7");
+        assert_eq!(ctx.preferred_language(&["en-US", "ja"]), Some("en-US"));
+
+        let ctx = Context::test(Method::GET, "/")
+            .with_header("Accept-Language", "de, *;q=0.1, ja;q=0");
+        assert_eq!(ctx.preferred_language(&["ja", "en"]), Some("en"));
+        assert_eq!(ctx.preferred_language(&["ja"]), None);
+
+        assert_eq!(Context::test(Method::GET, "/").preferred_language(&["zh", "en"]), Some("zh"));
+    }
+
+    #[test]
+    fn set_cookie_appends_to_response() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.set_cookie(Cookie::new("a", "1")).unwrap();
+        ctx.set_cookie(Cookie::new("b", "2").with_same_site(crate::cookie::SameSite::Lax))
+            .unwrap();
+
+        let cookies: Vec<_> = ctx.response().headers.get_all(http::header::SET_COOKIE).iter().collect();
+        assert_eq!(cookies, ["a=1", "b=2; SameSite=Lax"]);
+    }
+
+    #[test]
+    fn param_required_reports_missing_params() {
+        let ctx = Context::test(Method::GET, "/").with_param("id", "7");
+        assert_eq!(ctx.param_required("id").unwrap(), "7");
+        assert!(matches!(ctx.param_required("slug"), Err(RinError::Internal(_))));
+    }
+
+    #[test]
+    fn header_getters_read_and_parse_values() {
+        let ctx = Context::test(Method::GET, "/")
+            .with_header("X-Request-Id", "abc")
+            .with_header("Content-Length", " 42")
+            .with_header("X-Retry", "soon");
+        assert_eq!(ctx.header("x-request-id"), Some("abc"));
+        assert_eq!(ctx.header("X-Missing"), None);
+
+        assert_eq!(ctx.header_parsed::<u64>("content-length").unwrap().unwrap(), 42);
+        assert!(matches!(ctx.header_parsed::<u32>("x-retry"), Some(Err(RinError::BadRequest(_)))));
+        assert!(ctx.header_parsed::<u32>("x-missing").is_none());
+    }
+
+    #[test]
+    fn preferred_media_type_prefers_specific_ranges() {
+        let ctx = Context::test(Method::GET, "/")
+            .with_header("Accept", "text/*;q=0.5, application/json");
+        assert_eq!(ctx.preferred_media_type(&["text/html", "application/json"]), Some("application/json"));
+
+        let ctx = Context::test(Method::GET, "/")
+            .with_header("Accept", "text/html;q=0.9, */*;q=0.1, text/plain;q=0");
+        assert_eq!(ctx.preferred_media_type(&["application/json", "text/html"]), Some("text/html"));
+        assert_eq!(ctx.preferred_media_type(&["text/plain"]), None);
+        assert_eq!(ctx.preferred_media_type(&["image/png"]), Some("image/png"));
+    }
+
+    #[test]
+    fn if_match_precondition() {
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Match", r#""v1", "v2""#);
+        assert!(!ctx.precondition_failed_unless_match(r#""v2""#));
+        assert_eq!(ctx.response().status, StatusCode::OK);
+
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Match", r#""v1""#);
+        assert!(ctx.precondition_failed_unless_match(r#""v2""#));
+        assert_eq!(ctx.response().status, StatusCode::PRECONDITION_FAILED);
+
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Match", "*");
+        assert!(!ctx.precondition_failed_unless_match(r#""v2""#));
+
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Match", r#"W/"v2""#);
+        assert!(ctx.precondition_failed_unless_match(r#"W/"v2""#));
+
+        assert!(!Context::test(Method::GET, "/").precondition_failed_unless_match(r#""v2""#));
+
+        // 实体标签的引号内可以出现逗号
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Match", r#""a,b", "c""#);
+        assert!(!ctx.precondition_failed_unless_match(r#""a,b""#));
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Match", r#""a,b", "c""#);
+        assert!(ctx.precondition_failed_unless_match(r#""a""#));
+    }
+
+    #[test]
+    fn if_unmodified_since_precondition() {
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let since = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        let header = "Sun, 06 Nov 1994 08:49:37 GMT";
+
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Unmodified-Since", header);
+        assert!(!ctx.precondition_failed_unless_unmodified_since(since + Duration::from_millis(500)));
+
+        let mut ctx = Context::test(Method::GET, "/").with_header("If-Unmodified-Since", header);
+        assert!(ctx.precondition_failed_unless_unmodified_since(since + Duration::from_secs(1)));
+        assert_eq!(ctx.response().status, StatusCode::PRECONDITION_FAILED);
+
+        let mut ctx = Context::test(Method::GET, "/")
+            .with_header("If-Unmodified-Since", header)
+            .with_header("If-Match", "*");
+        assert!(!ctx.precondition_failed_unless_unmodified_since(since + Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn if_unmodified_since_ignores_huge_years() {
+        let mut ctx = Context::test(Method::GET, "/")
+            .with_header("If-Unmodified-Since", "Sun, 06 Nov 999999999999 08:49:37 GMT");
+        assert!(!ctx.precondition_failed_unless_unmodified_since(SystemTime::now()));
+        assert_eq!(ctx.response().status, StatusCode::OK);
+    }
+
+    #[test]
+    fn write_appends_chunks() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.write("Hello");
+        ctx.write(", ");
+        ctx.write(String::from("Rin"));
+
+        let response = ctx.response();
+        assert_eq!(response.body, Bytes::from_static(b"Hello, Rin"));
+        assert_eq!(
+            response.headers.get(http::header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn write_reuses_the_body_buffer() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.write("x");
+        let mut reallocations = 0;
+        let mut last = ctx.response().body.as_ptr();
+        for _ in 0..4096 {
+            ctx.write("x");
+            let ptr = ctx.response().body.as_ptr();
+            if ptr != last {
+                reallocations += 1;
+                last = ptr;
+            }
+        }
+        assert_eq!(ctx.response().body.len(), 4097);
+        // 按倍数扩容时重新分配的次数是对数级的
+        assert!(reallocations < 32, "{} reallocations", reallocations);
+    }
+
+    #[test]
+    fn write_keeps_existing_content_type() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.headers_mut()
+            .insert(http::header::CONTENT_TYPE, http::HeaderValue::from_static("text/csv"));
+        ctx.write("a,b\n");
+        ctx.write("1,2\n");
+
+        let response = ctx.response();
+        assert_eq!(response.headers.get(http::header::CONTENT_TYPE).unwrap(), "text/csv");
+        assert_eq!(response.body, Bytes::from_static(b"a,b\n1,2\n"));
+    }
+
+    #[test]
+    fn add_vary_accumulates_without_duplicates() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.add_vary(http::header::ACCEPT_ENCODING);
+        ctx.add_vary(http::header::ACCEPT_LANGUAGE);
+        ctx.add_vary(http::header::ACCEPT_ENCODING);
+
+        let values: Vec<_> = ctx.response().headers.get_all(http::header::VARY).iter().collect();
+        assert_eq!(values, ["accept-encoding, accept-language"]);
+    }
+
+    #[test]
+    fn add_vary_keeps_existing_values() {
+        let mut ctx = Context::test(Method::GET, "/");
+        let non_ascii = http::HeaderValue::from_bytes(b"X-\xe9t\xe9").unwrap();
+        ctx.append_header(http::header::VARY, non_ascii);
+        ctx.append_header(http::header::VARY, http::HeaderValue::from_static("Accept"));
+        ctx.add_vary(http::header::ACCEPT);
+        ctx.add_vary(http::header::COOKIE);
+
+        let values: Vec<_> = ctx.response().headers.get_all(http::header::VARY).iter().collect();
+        assert_eq!(values, [&b"X-\xe9t\xe9, Accept, cookie"[..]]);
+
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.append_header(http::header::VARY, http::HeaderValue::from_static("*"));
+        ctx.add_vary(http::header::ACCEPT);
+        assert_eq!(ctx.response().headers.get(http::header::VARY).unwrap(), "*");
+    }
+
+    #[test]
+    fn set_expires_formats_imf_fixdate() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.set_expires(UNIX_EPOCH + Duration::from_secs(784_111_777));
+        assert_eq!(
+            ctx.response().headers.get(http::header::EXPIRES).unwrap(),
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+    }
+
+    #[test]
+    fn set_max_age_sets_both_headers() {
+        let mut ctx = Context::test(Method::GET, "/");
+        let before = SystemTime::now();
+        ctx.set_max_age(Duration::from_secs(3_600));
+
+        let headers = &ctx.response().headers;
+        assert_eq!(headers.get(http::header::CACHE_CONTROL).unwrap(), "max-age=3600");
+        let expires = headers.get(http::header::EXPIRES).unwrap().to_str().unwrap();
+        let expires = crate::date::parse_httpdate(expires).unwrap();
+        assert!(expires + Duration::from_secs(1) >= before + Duration::from_secs(3_600));
+    }
+
+    #[test]
+    fn set_max_age_clamps_far_future_expires() {
+        for max_age in [Duration::MAX, Duration::from_secs(u64::MAX), Duration::from_secs(1 << 40)] {
+            let mut ctx = Context::test(Method::GET, "/");
+            ctx.set_max_age(max_age);
+
+            let headers = &ctx.response().headers;
+            let expected = format!("max-age={}", max_age.as_secs());
+            assert_eq!(headers.get(http::header::CACHE_CONTROL).unwrap(), expected.as_str());
+            assert_eq!(
+                headers.get(http::header::EXPIRES).unwrap(),
+                "Fri, 31 Dec 9999 23:59:59 GMT"
+            );
+        }
+    }
+
+    async fn stream_status(ctx: Context) -> &'static str {
+        if ctx.is_shutting_down() { "closing" } else { "streaming" }
+    }
+
+    #[tokio::test]
+    async fn shutdown_signal_is_visible_inside_handlers() {
+        let signal = ShutdownSignal::new();
+        let new_context = || {
+            let mut ctx = Context::test(Method::GET, "/");
+            ctx.set_shutdown_signal(signal.clone());
+            ctx
+        };
+
+        let response = stream_status.handle(new_context()).await.unwrap();
+        assert_eq!(response.body, Bytes::from_static(b"streaming"));
+
+        signal.trigger();
+        let response = stream_status.handle(new_context()).await.unwrap();
+        assert_eq!(response.body, Bytes::from_static(b"closing"));
+
+        assert!(!Context::test(Method::GET, "/").is_shutting_down());
+    }
+
+    #[tokio::test]
+    async fn test_context_drives_a_handler() {
+        let ctx = Context::test(Method::POST, "/users/rin?greeting=Hi")
+            .with_header("User-Agent", "rin-test")
+            .with_body("abc")
+            .with_param("name", "rin");
+
+        let response = greet.handle(ctx).await.unwrap();
+        assert_eq!(response.body, Bytes::from_static(b"Hi, rin (rin-test, 3 bytes)"));
+    }
+
+    #[test]
+    fn found_returns_response_for_some() {
+        let response = Context::test(Method::GET, "/").found(Some("user")).unwrap();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body, Bytes::from_static(b"user"));
+    }
+
+    #[test]
+    fn found_returns_not_found_for_none() {
+        let err = Context::test(Method::GET, "/").found(None::<&'static str>).unwrap_err();
+        assert!(matches!(err, RinError::NotFound));
+    }
+}
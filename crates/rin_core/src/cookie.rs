@@ -0,0 +1,229 @@
+use crate::error::RinError;
+use http::HeaderValue;
+use std::fmt;
+use std::time::Duration;
+
+/// Cookie 的 `SameSite` 属性。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    /// 仅在同站请求中发送
+    Strict,
+    /// 同站请求及跨站的顶级导航（GET）中发送
+    Lax,
+    /// 所有请求中都发送；浏览器要求同时设置 `Secure`
+    None,
+}
+
+impl SameSite {
+    fn as_str(self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+/// 用于生成 `Set-Cookie` 响应头的 Cookie。
+///
+/// 通过 `with_*` 方法链式设置属性，再交给 `Response::with_cookie` 写入响应。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    domain: Option<String>,
+    max_age: Option<Duration>,
+    http_only: bool,
+    secure: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    /// 创建一个只包含名称和值的会话 Cookie。
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Cookie {
+            name: name.into(),
+            value: value.into(),
+            path: None,
+            domain: None,
+            max_age: None,
+            http_only: false,
+            secure: false,
+            same_site: None,
+        }
+    }
+
+    /// 设置 `Path` 属性。
+    pub fn with_path(mut self, path: impl Into<String>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// 设置 `Domain` 属性。
+    pub fn with_domain(mut self, domain: impl Into<String>) -> Self {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// 设置 `Max-Age` 属性，精确到秒。
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// 设置 `HttpOnly` 属性，禁止脚本读取该 Cookie。
+    pub fn with_http_only(mut self, http_only: bool) -> Self {
+        self.http_only = http_only;
+        self
+    }
+
+    /// 设置 `Secure` 属性，仅在 HTTPS 连接上发送该 Cookie。
+    pub fn with_secure(mut self, secure: bool) -> Self {
+        self.secure = secure;
+        self
+    }
+
+    /// 设置 `SameSite` 属性。
+    pub fn with_same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Cookie 名称。
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Cookie 的值（未编码）。
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// 转换为 `Set-Cookie` 头的值。
+    ///
+    /// 值会被百分号编码，名称和属性则原样输出，因此需要先校验，防止注入额外的属性。
+    ///
+    /// # Errors
+    /// 名称不是 RFC 6265 要求的 token，或 `Path`、`Domain` 含有 `;`、控制字符或非 ASCII 字符时，
+    /// 返回 `RinError::Internal`。
+    pub fn to_header_value(&self) -> Result<HeaderValue, RinError> {
+        if self.name.is_empty() || !self.name.bytes().all(is_token_char) {
+            return Err(RinError::Internal(format!("Invalid cookie name: {:?}", self.name)));
+        }
+        for (attribute, value) in [("Path", &self.path), ("Domain", &self.domain)] {
+            if let Some(value) = value
+                && !value.bytes().all(is_attribute_char)
+            {
+                return Err(RinError::Internal(format!(
+                    "Invalid {} for cookie {}: {:?}",
+                    attribute, self.name, value
+                )));
+            }
+        }
+        HeaderValue::from_str(&self.to_string())
+            .map_err(|_| RinError::Internal(format!("Invalid cookie: {:?}", self.name)))
+    }
+}
+
+// 按 RFC 6265 的 set-cookie-string 格式输出，值会被百分号编码
+impl fmt::Display for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}=", self.name)?;
+        for &b in self.value.as_bytes() {
+            if is_cookie_octet(b) {
+                write!(f, "{}", char::from(b))?;
+            } else {
+                write!(f, "%{:02X}", b)?;
+            }
+        }
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if let Some(domain) = &self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+// RFC 6265 中允许直接出现在 Cookie 值里的字节；`%` 本身也编码，以便无歧义地解码
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x24 | 0x26..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+// RFC 7230 的 tchar，Cookie 名称必须由它组成
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+}
+
+// `Path`、`Domain` 属性值允许的字节：可见 ASCII 与空格，但不能有结束属性的 `;`
+fn is_attribute_char(b: u8) -> bool {
+    matches!(b, 0x20..=0x7E) && b != b';'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_all_attributes() {
+        let cookie = Cookie::new("session", "abc123")
+            .with_path("/")
+            .with_domain("example.com")
+            .with_max_age(Duration::from_secs(3600))
+            .with_http_only(true)
+            .with_secure(true);
+        assert_eq!(
+            cookie.to_string(),
+            "session=abc123; Path=/; Domain=example.com; Max-Age=3600; HttpOnly; Secure"
+        );
+    }
+
+    #[test]
+    fn formats_same_site_and_encodes_value() {
+        let cookie = Cookie::new("prefs", "a b;c=\"é\"%")
+            .with_http_only(true)
+            .with_same_site(SameSite::Strict);
+        assert_eq!(
+            cookie.to_string(),
+            "prefs=a%20b%3Bc=%22%C3%A9%22%25; HttpOnly; SameSite=Strict"
+        );
+        assert_eq!(cookie.value(), "a b;c=\"é\"%");
+    }
+
+    #[test]
+    fn rejects_attribute_injection() {
+        assert!(Cookie::new("session", "x").with_path("/app").to_header_value().is_ok());
+
+        for name in ["", "a b", "a;b", "a=b", "a\"b", "a\tb", "é"] {
+            assert!(
+                matches!(Cookie::new(name, "x").to_header_value(), Err(RinError::Internal(_))),
+                "{:?}",
+                name
+            );
+        }
+        let injected = Cookie::new("session", "x").with_path("/; Domain=evil.example");
+        assert!(injected.to_header_value().is_err());
+        assert!(Cookie::new("session", "x").with_path("/\x7f").to_header_value().is_err());
+        assert!(Cookie::new("session", "x").with_domain("exämple.com").to_header_value().is_err());
+        assert!(Cookie::new("session", "x").with_domain("a.com;Secure").to_header_value().is_err());
+    }
+
+    #[test]
+    fn session_cookie_has_no_attributes() {
+        assert_eq!(Cookie::new("theme", "dark").to_string(), "theme=dark");
+    }
+}
@@ -0,0 +1,176 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+// 1970-01-01 是星期四
+const WEEKDAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+
+// HTTP 日期使用四位年份，更大的年份直接拒绝，避免换算秒数时溢出
+const MAX_YEAR: u64 = 9999;
+
+/// 能以四位年份表示的最晚时间 `Fri, 31 Dec 9999 23:59:59 GMT`，距 Unix 纪元的秒数。
+pub(crate) const MAX_HTTPDATE_SECS: u64 = 253_402_300_799;
+
+/// 将时间格式化为 RFC 7231 的 IMF-fixdate，例如 `Sun, 06 Nov 1994 08:49:37 GMT`。
+///
+/// 早于 Unix 纪元的时间按纪元处理，不足一秒的部分被截断。
+pub fn httpdate(t: SystemTime) -> String {
+    let secs = t.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+    let days = secs / 86_400;
+    let (year, month, day) = civil_from_days(days);
+    let rem = secs % 86_400;
+    format!(
+        "{}, {:02} {} {} {:02}:{:02}:{:02} GMT",
+        WEEKDAYS[(days % 7) as usize],
+        day,
+        MONTHS[(month - 1) as usize],
+        year,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+/// 解析 HTTP 日期（`If-Unmodified-Since`、`Expires` 等头部使用的格式）。
+///
+/// 支持 RFC 7231 要求接收方兼容的三种格式：
+/// - IMF-fixdate：`Sun, 06 Nov 1994 08:49:37 GMT`
+/// - RFC 850：`Sunday, 06-Nov-94 08:49:37 GMT`
+/// - asctime：`Sun Nov  6 08:49:37 1994`
+///
+/// 格式错误、早于 Unix 纪元或年份超过 9999 时返回 `None`。
+pub fn parse_httpdate(s: &str) -> Option<SystemTime> {
+    let fields: Vec<&str> = s.split_whitespace().collect();
+    let (day, month, year, time) = match fields.as_slice() {
+        // IMF-fixdate
+        [_, day, month, year, time, "GMT"] => (*day, *month, year.parse().ok()?, *time),
+        // RFC 850，两位年份简化为 1970-2069
+        [_, date, time, "GMT"] => {
+            let mut parts = date.split('-');
+            let (day, month, year) = (parts.next()?, parts.next()?, parts.next()?);
+            let year: u64 = year.parse().ok()?;
+            let year = if year < 70 { 2000 + year } else { 1900 + year };
+            (day, month, year, *time)
+        }
+        // asctime
+        [_, month, day, time, year] => (*day, *month, year.parse().ok()?, *time),
+        _ => return None,
+    };
+
+    let day: u64 = day.parse().ok()?;
+    let month = MONTHS.iter().position(|m| *m == month)? as u64 + 1;
+    let mut hms = time.split(':').map(|part| part.parse::<u64>().ok());
+    let (hour, minute, second) = (hms.next()??, hms.next()??, hms.next()??);
+    if hms.next().is_some()
+        || year > MAX_YEAR
+        || !(1..=days_in_month(year, month)).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day)?;
+    let secs = days
+        .checked_mul(86_400)?
+        .checked_add(hour * 3_600 + minute * 60 + second)?;
+    UNIX_EPOCH.checked_add(Duration::from_secs(secs))
+}
+
+// 某年某月的天数，按公历闰年规则处理二月
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400)) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// 公历日期到 Unix 纪元以来天数的转换（Howard Hinnant 的 days_from_civil 算法）
+fn days_from_civil(year: u64, month: u64, day: u64) -> Option<u64> {
+    if year < 1970 {
+        return None;
+    }
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y / 400;
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era.checked_mul(146_097)?.checked_add(doe)?.checked_sub(719_468)
+}
+
+// days_from_civil 的逆运算
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_all_three_formats() {
+        let expected = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(
+            parse_httpdate("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(expected)
+        );
+        assert_eq!(
+            parse_httpdate("Sunday, 06-Nov-94 08:49:37 GMT"),
+            Some(expected)
+        );
+        assert_eq!(parse_httpdate("Sun Nov  6 08:49:37 1994"), Some(expected));
+    }
+
+    #[test]
+    fn formats_imf_fixdate() {
+        assert_eq!(httpdate(UNIX_EPOCH), "Thu, 01 Jan 1970 00:00:00 GMT");
+        let t = UNIX_EPOCH + Duration::from_secs(784_111_777);
+        assert_eq!(httpdate(t), "Sun, 06 Nov 1994 08:49:37 GMT");
+        // 闰日
+        let t = UNIX_EPOCH + Duration::from_secs(951_782_400);
+        assert_eq!(httpdate(t), "Tue, 29 Feb 2000 00:00:00 GMT");
+        assert_eq!(parse_httpdate(&httpdate(t)), Some(t));
+    }
+
+    #[test]
+    fn rejects_malformed_dates() {
+        assert_eq!(parse_httpdate(""), None);
+        assert_eq!(parse_httpdate("Sun, 06 Foo 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_httpdate("Sun, 06 Nov 1994 25:49:37 GMT"), None);
+        assert_eq!(parse_httpdate("Sun, 06 Nov 1994 08:49:37 PST"), None);
+    }
+
+    #[test]
+    fn rejects_impossible_days() {
+        assert_eq!(parse_httpdate("Sat, 31 Feb 2024 00:00:00 GMT"), None);
+        assert_eq!(parse_httpdate("Thu, 31 Apr 2024 00:00:00 GMT"), None);
+        assert_eq!(parse_httpdate("Sun, 29 Feb 1970 00:00:00 GMT"), None);
+        assert_eq!(parse_httpdate("Mon, 29 Feb 2100 00:00:00 GMT"), None);
+        assert!(parse_httpdate("Thu, 29 Feb 2024 00:00:00 GMT").is_some());
+        assert!(parse_httpdate("Tue, 29 Feb 2000 00:00:00 GMT").is_some());
+    }
+
+    #[test]
+    fn rejects_huge_years() {
+        assert_eq!(parse_httpdate("Sun, 06 Nov 999999999999 08:49:37 GMT"), None);
+        assert_eq!(parse_httpdate("Sun Nov  6 08:49:37 18446744073709551615"), None);
+        assert_eq!(parse_httpdate("Sat, 01 Jan 10000 00:00:00 GMT"), None);
+        let max = UNIX_EPOCH + Duration::from_secs(MAX_HTTPDATE_SECS);
+        assert_eq!(parse_httpdate("Fri, 31 Dec 9999 23:59:59 GMT"), Some(max));
+    }
+}
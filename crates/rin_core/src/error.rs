@@ -1,7 +1,8 @@
 // rin-core/src/error.rs
 
 use thiserror::Error as ThisError; // 使用 ThisError 来 derive 错误 trait
-use std::fmt::{self, Display};
+use http::StatusCode;
+use std::convert::Infallible;
 use std::error::Error as StdError; // 用于 Anyhow 变体中的 Box<dyn StdError>
 
 /// Rin 框架的通用错误类型。
@@ -24,6 +25,9 @@ pub enum RinError {
     #[error("Forbidden")]
     Forbidden, // HTTP 403
 
+    #[error("Payload Too Large")]
+    PayloadTooLarge, // HTTP 413，请求体超出限制
+
     #[error("Internal Server Error: {0}")]
     Internal(String), // HTTP 500，服务器内部错误
 
@@ -40,5 +44,118 @@ pub enum RinError {
     Anyhow(#[from] anyhow::Error),
 }
 
+impl RinError {
+    /// 该错误对应的 HTTP 状态码。
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            RinError::NotFound => StatusCode::NOT_FOUND,
+            RinError::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            RinError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            RinError::Unauthorized => StatusCode::UNAUTHORIZED,
+            RinError::Forbidden => StatusCode::FORBIDDEN,
+            RinError::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            RinError::Internal(_) | RinError::Other(_) | RinError::Anyhow(_) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        }
+    }
+
+    /// 适合返回给客户端的错误信息。
+    ///
+    /// 5xx 错误只返回标准原因短语，避免把内部细节暴露给客户端。
+    pub fn public_message(&self) -> String {
+        let status = self.status_code();
+        if status.is_server_error() {
+            status.canonical_reason().unwrap_or_default().to_string()
+        } else {
+            self.to_string()
+        }
+    }
+
+    /// 尝试取回被包装的原始错误。
+    ///
+    /// 仅对 `Other` 和 `Anyhow` 变体有效，其余变体或类型不匹配时返回 `None`。
+    pub fn downcast_ref<T: StdError + Send + Sync + 'static>(&self) -> Option<&T> {
+        match self {
+            RinError::Other(e) => e.downcast_ref::<T>(),
+            RinError::Anyhow(e) => e.downcast_ref::<T>(),
+            _ => None,
+        }
+    }
+}
+
+// 让处理函数中的 `?` 可以直接作用于 IO 错误。
+// 只有 `NotFound` 映射为 404；权限等其余错误都视为服务器内部错误，避免向客户端暴露文件系统状态。
+impl From<std::io::Error> for RinError {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::NotFound => RinError::NotFound,
+            _ => RinError::Internal(e.to_string()),
+        }
+    }
+}
+
+// JSON 错误通常来自解析客户端提交的数据，因此映射为 400。
+// 序列化响应失败属于服务器错误，应显式转换为 `RinError::Internal`。
+impl From<serde_json::Error> for RinError {
+    fn from(e: serde_json::Error) -> Self {
+        RinError::BadRequest(format!("Invalid JSON: {}", e))
+    }
+}
+
+// 允许不会失败的处理函数返回 `Result<T, Infallible>`
+impl From<Infallible> for RinError {
+    fn from(never: Infallible) -> Self {
+        match never {}
+    }
+}
+
 // 为了保持与之前 `rin_core::Error` 的使用习惯一致，你可以在 `lib.rs` 中将 `RinError` 重新导出为 `Error`。
-// 这样用户在导入时仍然可以使用 `use rin_core::Error;`。
\ No newline at end of file
+// 这样用户在导入时仍然可以使用 `use rin_core::Error;`。
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, ThisError, PartialEq)]
+    #[error("quota exceeded: {0}")]
+    struct QuotaError(u32);
+
+    #[test]
+    fn downcast_ref_recovers_wrapped_errors() {
+        let boxed: Box<dyn StdError + Send + Sync> = Box::new(QuotaError(3));
+        let err = RinError::from(boxed);
+        assert_eq!(err.downcast_ref::<QuotaError>(), Some(&QuotaError(3)));
+        assert_eq!(err.to_string(), "quota exceeded: 3");
+
+        let err = RinError::from(anyhow::Error::new(QuotaError(5)));
+        assert_eq!(err.downcast_ref::<QuotaError>(), Some(&QuotaError(5)));
+
+        assert!(RinError::NotFound.downcast_ref::<QuotaError>().is_none());
+        assert!(err.downcast_ref::<std::fmt::Error>().is_none());
+    }
+
+    #[test]
+    fn io_errors_map_by_kind() {
+        use std::io::{Error, ErrorKind};
+
+        assert!(matches!(RinError::from(Error::from(ErrorKind::NotFound)), RinError::NotFound));
+        assert!(matches!(
+            RinError::from(Error::from(ErrorKind::PermissionDenied)),
+            RinError::Internal(_)
+        ));
+        assert!(matches!(
+            RinError::from(Error::other("disk on fire")),
+            RinError::Internal(msg) if msg == "disk on fire"
+        ));
+    }
+
+    #[test]
+    fn json_errors_map_to_bad_request() {
+        fn parse(body: &str) -> Result<serde_json::Value, RinError> {
+            Ok(serde_json::from_str(body)?)
+        }
+
+        assert!(parse("{}").is_ok());
+        assert!(matches!(parse("{oops"), Err(RinError::BadRequest(_))));
+    }
+}
@@ -1,6 +1,6 @@
 use crate::context::Context;
 use crate::error::RinError;
-use crate::response::Response;
+use crate::response::{IntoResponse, Response};
 use async_trait::async_trait;
 use std::future::Future;
 use std::pin::Pin;
@@ -25,15 +25,69 @@ pub trait Handler: Send + Sync + 'static {
     async fn handle(&self, ctx: Context) -> Result<Response, RinError>;
 }
 
+/// 处理函数的返回值。
+///
+/// 既可以是 `Result<T, E>`（`T: IntoResponse`，`E: Into<RinError>`），
+/// 也可以是直接实现了 `IntoResponse` 的类型，这样不会失败的处理函数无需声明错误类型。
+pub trait IntoHandlerResult {
+    /// 转换为处理函数的标准返回值。
+    fn into_handler_result(self) -> Result<Response, RinError>;
+}
+
+impl<T: IntoResponse> IntoHandlerResult for T {
+    fn into_handler_result(self) -> Result<Response, RinError> {
+        Ok(self.into_response())
+    }
+}
+
+impl<T: IntoResponse, E: Into<RinError>> IntoHandlerResult for Result<T, E> {
+    fn into_handler_result(self) -> Result<Response, RinError> {
+        self.map(IntoResponse::into_response).map_err(Into::into)
+    }
+}
+
 // 为满足 Handler Trait 要求的函数/闭包自动实现 Handler Trait
 // 这样，用户可以直接提供 async fn 或 async move closure
 #[async_trait]
-impl<F, Fut> Handler for F
+impl<F, Fut, R> Handler for F
 where
     F: Fn(Context) -> Fut + Send + Sync + 'static,
-    Fut: Future<Output = Result<Response, RinError>> + Send + 'static,
+    Fut: Future<Output = R> + Send + 'static,
+    R: IntoHandlerResult,
 {
     async fn handle(&self, ctx: Context) -> Result<Response, RinError> {
-        self(ctx).await
+        self(ctx).await.into_handler_result()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use http::Method;
+    use std::convert::Infallible;
+
+    async fn plain(_ctx: Context) -> &'static str {
+        "plain"
+    }
+
+    async fn infallible(_ctx: Context) -> Result<String, Infallible> {
+        Ok("infallible".to_string())
+    }
+
+    async fn fallible(_ctx: Context) -> Result<Response, RinError> {
+        Err(RinError::NotFound)
+    }
+
+    #[tokio::test]
+    async fn handlers_may_omit_the_error_type() {
+        let response = plain.handle(Context::test(Method::GET, "/")).await.unwrap();
+        assert_eq!(response.body, Bytes::from_static(b"plain"));
+
+        let response = infallible.handle(Context::test(Method::GET, "/")).await.unwrap();
+        assert_eq!(response.body, Bytes::from_static(b"infallible"));
+
+        let err = fallible.handle(Context::test(Method::GET, "/")).await.unwrap_err();
+        assert!(matches!(err, RinError::NotFound));
     }
 }
@@ -1,14 +1,23 @@
+pub mod content_type;
 pub mod context;
+pub mod cookie;
+pub mod date;
 pub mod error;
 pub mod handler;
 pub mod request;
 pub mod response; // 导出 prelude 模块
+pub mod shutdown;
+#[cfg(feature = "serde")]
+pub mod status_serde;
 
+pub use content_type::ContentType;
 pub use context::Context;
+pub use cookie::{Cookie, SameSite};
 pub use error::RinError;
-pub use handler::{Handler, HandlerFunc};
+pub use handler::{Handler, HandlerFunc, IntoHandlerResult};
 pub use request::Request;
-pub use response::{IntoResponse, Response};
+pub use response::{IntoResponse, Response, WithStatus};
+pub use shutdown::ShutdownSignal;
 
 pub use bytes::Bytes;
 pub use http::{HeaderMap, HeaderValue, Method, StatusCode, Uri, header};
@@ -1,15 +1,100 @@
+pub mod uri;
+
+use crate::content_type::ContentType;
+use crate::error::RinError;
+use uri::{decode_query_component, encode_query_component};
 use bytes::Bytes;
-use http::{HeaderMap, Method, Uri};
+use http::header::{
+    AUTHORIZATION, AsHeaderName, CONNECTION, CONTENT_LENGTH, CONTENT_TYPE, COOKIE, HeaderName,
+    PROXY_AUTHORIZATION, SET_COOKIE,
+};
+use http::{HeaderMap, Method, Uri, Version};
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::fmt;
 use std::path::PathBuf;
 
+/// 默认在 `Debug` 输出中隐藏取值的敏感头部。
+pub static SENSITIVE_HEADERS: [HeaderName; 4] =
+    [AUTHORIZATION, COOKIE, SET_COOKIE, PROXY_AUTHORIZATION];
+
+/// 以 `***` 代替敏感头部取值的 `Debug` 包装，避免凭据泄露到日志中。
+pub struct RedactedHeaders<'a> {
+    headers: &'a HeaderMap,
+    sensitive: &'a [HeaderName],
+}
+
+impl<'a> RedactedHeaders<'a> {
+    /// 使用默认的敏感头部集合 `SENSITIVE_HEADERS`。
+    pub fn new(headers: &'a HeaderMap) -> Self {
+        Self::with_sensitive(headers, &SENSITIVE_HEADERS)
+    }
+
+    /// 使用自定义的敏感头部集合。
+    pub fn with_sensitive(headers: &'a HeaderMap, sensitive: &'a [HeaderName]) -> Self {
+        RedactedHeaders { headers, sensitive }
+    }
+}
+
+impl fmt::Debug for RedactedHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut map = f.debug_map();
+        for (name, value) in self.headers {
+            if self.sensitive.contains(name) {
+                map.entry(name, &"***");
+            } else {
+                map.entry(name, value);
+            }
+        }
+        map.finish()
+    }
+}
+
+/// 以 `Name: value\r\n` 的报文格式输出整组头部，便于调试和记录日志。
+///
+/// 头部按名称排序，同名的多个值保持原有顺序，因此输出是确定的；
+/// `SENSITIVE_HEADERS` 中的头部取值以 `***` 代替，非 UTF-8 的取值按有损方式输出。
+pub struct DisplayHeaders<'a>(pub &'a HeaderMap);
+
+impl fmt::Display for DisplayHeaders<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut names: Vec<&HeaderName> = self.0.keys().collect();
+        names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        for name in names {
+            for value in self.0.get_all(name) {
+                if SENSITIVE_HEADERS.contains(name) {
+                    write!(f, "{}: ***\r\n", name)?;
+                } else {
+                    write!(f, "{}: {}\r\n", name, String::from_utf8_lossy(value.as_bytes()))?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 请求行中请求目标的形式（RFC 7230 第 5.3 节）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestTarget {
+    /// `/path?query`，普通请求使用的形式
+    Origin,
+    /// `http://host/path`，发往正向代理的请求使用的形式
+    Absolute,
+    /// `host:port`，仅用于 CONNECT
+    Authority,
+    /// `*`，仅用于服务器级别的 OPTIONS
+    Asterisk,
+}
+
 /// 表示一个高层次的 HTTP 请求。
-#[derive(Debug, Clone)] // Clone 是为了在 Context 中传递 ownership 或做一些内部克隆
+#[derive(Clone)] // Clone 是为了在 Context 中传递 ownership 或做一些内部克隆
 pub struct Request {
     /// HTTP 方法 (GET, POST, etc.)
     pub method: Method,
     /// 请求的 URI (路径, 查询参数等)
     pub uri: Uri,
+    /// HTTP 协议版本，默认为 HTTP/1.1
+    pub version: Version,
     /// 请求头集合
     pub headers: HeaderMap,
     /// 请求体原始字节数据
@@ -24,16 +109,156 @@ impl Request {
         Request {
             method,
             uri,
+            version: Version::HTTP_11,
             headers,
             body_bytes,
             params: HashMap::new(), // 初始为空，由路由器填充
         }
     }
 
+    /// 根据方法和 URI 判断请求目标的形式，正向代理据此区分 CONNECT 隧道与普通转发。
+    pub fn target_form(&self) -> RequestTarget {
+        if self.uri.scheme().is_some() {
+            RequestTarget::Absolute
+        } else if self.uri.authority().is_some() {
+            RequestTarget::Authority
+        } else if self.uri.path() == "*" {
+            RequestTarget::Asterisk
+        } else {
+            RequestTarget::Origin
+        }
+    }
+
+    /// 解析 `Content-Length` 头。
+    ///
+    /// 头不存在、不是纯十进制数字（`+42` 之类的写法同样拒绝），或多个值互相矛盾时返回 `None`。
+    pub fn content_length(&self) -> Option<u64> {
+        let mut values = self.list_values(CONTENT_LENGTH).map(|value| {
+            // u64::from_str 接受前导 `+`，RFC 9110 只允许 1*DIGIT
+            if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            value.parse::<u64>().ok()
+        });
+        let first = values.next()??;
+        values.all(|value| value == Some(first)).then_some(first)
+    }
+
+    /// 处理完该请求后是否应保持连接。
+    ///
+    /// `Connection: close` 总是关闭，`Connection: keep-alive` 总是保持；
+    /// 两者都没有时，HTTP/1.1 及以上默认保持，HTTP/1.0 默认关闭。
+    pub fn is_keep_alive(&self) -> bool {
+        let mut keep_alive = false;
+        for option in self.list_values(CONNECTION) {
+            if option.eq_ignore_ascii_case("close") {
+                return false;
+            }
+            keep_alive |= option.eq_ignore_ascii_case("keep-alive");
+        }
+        keep_alive || self.version >= Version::HTTP_11
+    }
+
     /// 获取请求的 URL 查询字符串。
     pub fn query_str(&self) -> Option<&str> {
         self.uri.query()
     }
+
+    /// 将列表型请求头（如 `Accept`、`Accept-Encoding`）展开为扁平的取值序列。
+    ///
+    /// 同时处理多行同名头部和单行内以逗号分隔的取值，并去除两侧空白。
+    /// 无法解析为可见 ASCII 的行会被跳过。
+    pub fn list_values<K: AsHeaderName>(&self, name: K) -> impl Iterator<Item = &str> {
+        self.headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|line| line.split(','))
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+    }
+
+    /// 获取请求的 `Content-Type` 头，无法解析为可见 ASCII 时返回 `None`。
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok())
+    }
+
+    /// 按 `Content-Type` 中声明的字符集将请求体解码为文本。
+    ///
+    /// 未声明字符集时按 UTF-8 处理；目前支持 `utf-8`、`us-ascii` 和 `iso-8859-1`。
+    /// 请求体本身是合法 UTF-8 时直接借用，不会分配。
+    ///
+    /// # Errors
+    /// 字符集不受支持或请求体不符合声明的编码时返回 `RinError::BadRequest`。
+    pub fn text_body(&self) -> Result<Cow<'_, str>, RinError> {
+        let body = &self.body_bytes[..];
+        match self.charset().as_deref() {
+            None | Some("utf-8") | Some("utf8") => std::str::from_utf8(body)
+                .map(Cow::Borrowed)
+                .map_err(|e| RinError::BadRequest(format!("Invalid UTF-8 body: {}", e))),
+            Some("us-ascii") | Some("ascii") => {
+                if body.is_ascii() {
+                    // ASCII 是 UTF-8 的子集
+                    Ok(Cow::Borrowed(std::str::from_utf8(body).expect("ASCII 总是合法的 UTF-8")))
+                } else {
+                    Err(RinError::BadRequest("Invalid US-ASCII body".to_string()))
+                }
+            }
+            Some("iso-8859-1") | Some("latin1") => Ok(decode_latin1(body)),
+            Some(other) => Err(RinError::BadRequest(format!("Unsupported charset: {}", other))),
+        }
+    }
+
+    /// 与 `text_body` 相同，但不会失败：非法字节替换为 `U+FFFD`，
+    /// 不支持的字符集按 UTF-8 处理。
+    pub fn text_body_lossy(&self) -> Cow<'_, str> {
+        let body = &self.body_bytes[..];
+        match self.charset().as_deref() {
+            Some("iso-8859-1") | Some("latin1") => decode_latin1(body),
+            _ => String::from_utf8_lossy(body),
+        }
+    }
+
+    // 提取 Content-Type 中的 charset 参数，统一为小写
+    fn charset(&self) -> Option<String> {
+        let content_type = ContentType::parse(self.headers.get(CONTENT_TYPE)?).ok()?;
+        content_type.get_param("charset").map(str::to_ascii_lowercase)
+    }
+}
+
+// 拆分列表项中的 `q` 权重，例如 `fr;q=0.9` -> ("fr", 0.9)，未给出时为 1.0
+pub(crate) fn split_quality(item: &str) -> (&str, f32) {
+    let mut parts = item.split(';');
+    let value = parts.next().unwrap_or("").trim();
+    let quality = parts
+        .filter_map(|param| param.split_once('='))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+        .and_then(|(_, q)| q.trim().parse::<f32>().ok())
+        .map_or(1.0, |q| q.clamp(0.0, 1.0));
+    (value, quality)
+}
+
+// ISO-8859-1 的每个字节恰好对应同值的 Unicode 码位
+fn decode_latin1(body: &[u8]) -> Cow<'_, str> {
+    if body.is_ascii() {
+        Cow::Borrowed(std::str::from_utf8(body).expect("ASCII 总是合法的 UTF-8"))
+    } else {
+        Cow::Owned(body.iter().map(|&b| char::from(b)).collect())
+    }
+}
+
+// 手动实现 Debug，隐藏 Authorization、Cookie 等敏感头部的取值
+impl fmt::Debug for Request {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Request")
+            .field("method", &self.method)
+            .field("uri", &self.uri)
+            .field("version", &self.version)
+            .field("headers", &RedactedHeaders::new(&self.headers))
+            .field("body_bytes", &self.body_bytes)
+            .field("params", &self.params)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -49,6 +274,38 @@ pub enum QueryValue {
     Multiple(Vec<String>),
 }
 
+impl QueryValue {
+    /// 返回第一个值。
+    pub fn as_str(&self) -> &str {
+        match self {
+            QueryValue::Single(s) => s,
+            QueryValue::Multiple(vec) => vec.first().map_or("", String::as_str),
+        }
+    }
+
+    /// 以切片列表形式返回全部值。
+    pub fn as_vec(&self) -> Vec<&str> {
+        match self {
+            QueryValue::Single(s) => vec![s.as_str()],
+            QueryValue::Multiple(vec) => vec.iter().map(String::as_str).collect(),
+        }
+    }
+
+    /// 取出全部值。
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            QueryValue::Single(s) => vec![s],
+            QueryValue::Multiple(vec) => vec,
+        }
+    }
+}
+
+impl Default for QueryCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl QueryCache {
     // 创建空的查询缓存
     pub fn new() -> Self {
@@ -57,7 +314,7 @@ impl QueryCache {
         }
     }
 
-    // 从查询字符串解析并填充缓存
+    // 从查询字符串解析并填充缓存，键和值都会被 URL 解码
     pub fn parse(query: &str) -> Self {
         let mut cache = QueryCache::new();
         if !query.is_empty() {
@@ -65,12 +322,33 @@ impl QueryCache {
                 let mut kv = pair.splitn(2, '=');
                 let key = kv.next().unwrap_or("");
                 let value = kv.next().unwrap_or("");
-                cache.insert(key.to_string(), value.to_string());
+                cache.insert(decode_query_component(key), decode_query_component(value));
             }
         }
         cache
     }
 
+    /// 将缓存重新序列化为查询字符串（不含开头的 `?`）。
+    ///
+    /// 键和值都会被百分号编码；为保证输出稳定，参数按键名排序，
+    /// 同一个键的多个值保持插入顺序。
+    pub fn to_query_string(&self) -> String {
+        let mut keys: Vec<_> = self.params.keys().collect();
+        keys.sort();
+
+        let mut pairs = Vec::new();
+        for key in keys {
+            for value in self.params[key].as_vec() {
+                pairs.push(format!(
+                    "{}={}",
+                    encode_query_component(key),
+                    encode_query_component(value)
+                ));
+            }
+        }
+        pairs.join("&")
+    }
+
     // 插入键值对，支持多值参数
     pub fn insert(&mut self, key: String, value: String) {
         self.params
@@ -88,10 +366,7 @@ impl QueryCache {
 
     // 获取单个值
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.params.get(key).and_then(|v| match v {
-            QueryValue::Single(s) => Some(s.as_str()),
-            QueryValue::Multiple(vec) => vec.first().map(|s| s.as_str()),
-        })
+        self.params.get(key).map(QueryValue::as_str)
     }
 
     // 获取所有值（多值参数）
@@ -102,6 +377,7 @@ impl QueryCache {
         })
     }
 }
+
 // 表单缓存结构
 #[derive(Debug, Clone)]
 pub struct FormCache {
@@ -127,6 +403,75 @@ pub struct FileEntry {
     mime_type: String, // MIME 类型
 }
 
+impl FormValue {
+    /// 返回第一个值。
+    pub fn as_str(&self) -> &str {
+        match self {
+            FormValue::Single(s) => s,
+            FormValue::Multiple(vec) => vec.first().map_or("", String::as_str),
+        }
+    }
+
+    /// 以切片列表形式返回全部值。
+    pub fn as_vec(&self) -> Vec<&str> {
+        match self {
+            FormValue::Single(s) => vec![s.as_str()],
+            FormValue::Multiple(vec) => vec.iter().map(String::as_str).collect(),
+        }
+    }
+
+    /// 取出全部值。
+    pub fn into_vec(self) -> Vec<String> {
+        match self {
+            FormValue::Single(s) => vec![s],
+            FormValue::Multiple(vec) => vec,
+        }
+    }
+}
+
+impl FileEntry {
+    /// 为已写入临时文件的上传创建元数据。
+    pub fn new(
+        filename: impl Into<String>,
+        path: impl Into<PathBuf>,
+        size: u64,
+        mime_type: impl Into<String>,
+    ) -> Self {
+        FileEntry {
+            filename: filename.into(),
+            path: path.into(),
+            size,
+            mime_type: mime_type.into(),
+        }
+    }
+
+    /// 客户端上传时提供的文件名。
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+
+    /// 临时文件路径。
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    /// 文件大小（字节）。
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// 文件的 MIME 类型。
+    pub fn mime_type(&self) -> &str {
+        &self.mime_type
+    }
+}
+
+impl Default for FormCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FormCache {
     // 创建空的表单缓存
     pub fn new() -> Self {
@@ -155,16 +500,13 @@ impl FormCache {
     pub fn insert_file(&mut self, key: String, file: FileEntry) {
         self.files
             .entry(key)
-            .or_insert_with(Vec::new)
+            .or_default()
             .push(file);
     }
 
     // 获取单个字段值
     pub fn get_field(&self, key: &str) -> Option<&str> {
-        self.fields.get(key).and_then(|v| match v {
-            FormValue::Single(s) => Some(s.as_str()),
-            FormValue::Multiple(vec) => vec.first().map(|s| s.as_str()),
-        })
+        self.fields.get(key).map(FormValue::as_str)
     }
 
     // 获取所有字段值
@@ -179,4 +521,182 @@ impl FormCache {
     pub fn get_files(&self, key: &str) -> Option<&Vec<FileEntry>> {
         self.files.get(key)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn debug_redacts_sensitive_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret-token"));
+        headers.insert(COOKIE, HeaderValue::from_static("session=abc123"));
+        headers.insert(http::header::ACCEPT, HeaderValue::from_static("text/html"));
+        let request = Request::new(Method::GET, Uri::from_static("/"), headers, Bytes::new());
+
+        let output = format!("{:?}", request);
+        assert!(output.contains(r#""authorization": "***""#));
+        assert!(output.contains(r#""cookie": "***""#));
+        assert!(output.contains(r#""accept": "text/html""#));
+        assert!(!output.contains("secret-token"));
+        assert!(!output.contains("abc123"));
+    }
+
+    fn request_with_headers(version: Version, pairs: &[(&'static str, &'static str)]) -> Request {
+        let mut headers = HeaderMap::new();
+        for &(name, value) in pairs {
+            headers.append(name, HeaderValue::from_static(value));
+        }
+        let mut request = Request::new(Method::GET, Uri::from_static("/"), headers, Bytes::new());
+        request.version = version;
+        request
+    }
+
+    #[test]
+    fn content_length_parses_consistent_values() {
+        let request = request_with_headers(Version::HTTP_11, &[("content-length", "42")]);
+        assert_eq!(request.content_length(), Some(42));
+
+        let request = request_with_headers(Version::HTTP_11, &[("content-length", "42, 42")]);
+        assert_eq!(request.content_length(), Some(42));
+
+        for value in ["-1", "+42", "abc", "4 2", "42, 43", "18446744073709551616"] {
+            let request = request_with_headers(Version::HTTP_11, &[("content-length", value)]);
+            assert_eq!(request.content_length(), None, "{}", value);
+        }
+        assert_eq!(request_with_headers(Version::HTTP_11, &[]).content_length(), None);
+    }
+
+    #[test]
+    fn keep_alive_follows_version_and_connection() {
+        assert!(request_with_headers(Version::HTTP_11, &[]).is_keep_alive());
+        assert!(!request_with_headers(Version::HTTP_10, &[]).is_keep_alive());
+        assert!(!request_with_headers(Version::HTTP_11, &[("connection", "close")]).is_keep_alive());
+        assert!(request_with_headers(Version::HTTP_10, &[("connection", "Keep-Alive")]).is_keep_alive());
+        assert!(!request_with_headers(Version::HTTP_11, &[("connection", "upgrade, close")]).is_keep_alive());
+    }
+
+    #[test]
+    fn target_form_classifies_request_targets() {
+        let cases = [
+            (Method::GET, "/index.html?x=1", RequestTarget::Origin),
+            (Method::GET, "http://example.com/path", RequestTarget::Absolute),
+            (Method::CONNECT, "example.com:443", RequestTarget::Authority),
+            (Method::OPTIONS, "*", RequestTarget::Asterisk),
+        ];
+        for (method, uri, expected) in cases {
+            let request = Request::new(method, uri.parse().unwrap(), HeaderMap::new(), Bytes::new());
+            assert_eq!(request.target_form(), expected, "{}", uri);
+        }
+    }
+
+    #[test]
+    fn list_values_flattens_lines_and_commas() {
+        let mut headers = HeaderMap::new();
+        headers.append(http::header::ACCEPT, HeaderValue::from_static("text/html, application/xhtml+xml"));
+        headers.append(http::header::ACCEPT, HeaderValue::from_static("application/xml;q=0.9 ,*/*;q=0.8"));
+        let request = Request::new(Method::GET, Uri::from_static("/"), headers, Bytes::new());
+
+        let values: Vec<_> = request.list_values(http::header::ACCEPT).collect();
+        assert_eq!(
+            values,
+            ["text/html", "application/xhtml+xml", "application/xml;q=0.9", "*/*;q=0.8"]
+        );
+        assert_eq!(request.list_values("accept-encoding").count(), 0);
+    }
+
+    fn request_with_body(content_type: Option<&'static str>, body: &'static [u8]) -> Request {
+        let mut headers = HeaderMap::new();
+        if let Some(content_type) = content_type {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+        Request::new(Method::POST, Uri::from_static("/"), headers, Bytes::from_static(body))
+    }
+
+    #[test]
+    fn text_body_decodes_utf8_without_copying() {
+        let request = request_with_body(Some("text/plain; charset=UTF-8"), "héllo".as_bytes());
+        assert_eq!(request.content_type(), Some("text/plain; charset=UTF-8"));
+        assert!(matches!(request.text_body().unwrap(), Cow::Borrowed("héllo")));
+
+        let invalid = request_with_body(None, b"\xff");
+        assert!(matches!(invalid.text_body(), Err(RinError::BadRequest(_))));
+        assert_eq!(invalid.text_body_lossy(), "\u{FFFD}");
+    }
+
+    #[test]
+    fn text_body_honors_declared_charset() {
+        let request = request_with_body(Some("text/plain; charset=\"ISO-8859-1\""), b"caf\xe9");
+        assert_eq!(request.text_body().unwrap(), "café");
+
+        let unsupported = request_with_body(Some("text/plain; charset=shift_jis"), b"abc");
+        assert!(matches!(unsupported.text_body(), Err(RinError::BadRequest(_))));
+        assert_eq!(unsupported.text_body_lossy(), "abc");
+    }
+
+    #[test]
+    fn query_cache_round_trips_through_query_string() {
+        let cache = QueryCache::parse("tag=b&name=hello%20world&tag=a+c&sym=%26%3D");
+        assert_eq!(cache.get("name"), Some("hello world"));
+        assert_eq!(cache.get("sym"), Some("&="));
+
+        let serialized = cache.to_query_string();
+        assert_eq!(serialized, "name=hello%20world&sym=%26%3D&tag=b&tag=a%20c");
+
+        let reparsed = QueryCache::parse(&serialized);
+        assert_eq!(reparsed.to_query_string(), serialized);
+        assert_eq!(reparsed.get_all("tag").unwrap(), &["b", "a c"]);
+    }
+
+    #[test]
+    fn query_value_accessors() {
+        let single = QueryValue::Single("a".to_string());
+        assert_eq!(single.as_str(), "a");
+        assert_eq!(single.as_vec(), ["a"]);
+        assert_eq!(single.into_vec(), ["a"]);
+
+        let multiple = QueryValue::Multiple(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(multiple.as_str(), "a");
+        assert_eq!(multiple.as_vec(), ["a", "b"]);
+        assert_eq!(multiple.into_vec(), ["a", "b"]);
+    }
+
+    #[test]
+    fn form_value_accessors() {
+        let single = FormValue::Single("x".to_string());
+        assert_eq!(single.as_str(), "x");
+        assert_eq!(single.as_vec(), ["x"]);
+        assert_eq!(single.into_vec(), ["x"]);
+
+        let multiple = FormValue::Multiple(vec!["x".to_string(), "y".to_string()]);
+        assert_eq!(multiple.as_str(), "x");
+        assert_eq!(multiple.as_vec(), ["x", "y"]);
+        assert_eq!(multiple.into_vec(), ["x", "y"]);
+    }
+
+    #[test]
+    fn display_headers_is_sorted_and_redacted() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-b", HeaderValue::from_static("2"));
+        headers.append("accept", HeaderValue::from_static("text/html"));
+        headers.append("accept", HeaderValue::from_static("*/*"));
+        headers.insert(AUTHORIZATION, HeaderValue::from_static("Bearer secret"));
+
+        assert_eq!(
+            DisplayHeaders(&headers).to_string(),
+            "accept: text/html\r\naccept: */*\r\nauthorization: ***\r\nx-b: 2\r\n"
+        );
+        assert_eq!(DisplayHeaders(&HeaderMap::new()).to_string(), "");
+    }
+
+    #[test]
+    fn redacted_headers_accepts_custom_names() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("k-123"));
+        let sensitive = [HeaderName::from_static("x-api-key")];
+
+        let output = format!("{:?}", RedactedHeaders::with_sensitive(&headers, &sensitive));
+        assert_eq!(output, r#"{"x-api-key": "***"}"#);
+    }
+}
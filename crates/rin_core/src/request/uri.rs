@@ -0,0 +1,91 @@
+use std::borrow::Cow;
+
+/// 按路径语义解码 `%XX` 转义序列，例如 `/hello%20world` -> `/hello world`。
+///
+/// 与查询参数不同，`+` 保持原样。格式错误的转义序列原样保留，
+/// 解码结果不是合法 UTF-8 时以 U+FFFD 替换。没有需要解码的内容时直接借用输入。
+///
+/// 注意 `%2F` 会被解码为 `/`，应在按 `/` 拆分路径段之后再解码每一段。
+pub fn decode_percent(input: &str) -> Cow<'_, str> {
+    decode(input, false)
+}
+
+// 解码查询参数中的单个组件：`+` 视为空格，其余与路径相同
+pub(crate) fn decode_query_component(s: &str) -> String {
+    decode(s, true).into_owned()
+}
+
+// 百分号编码查询参数中的单个组件，仅保留 RFC 3986 的非保留字符
+pub(crate) fn encode_query_component(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(b));
+        } else {
+            encoded.push_str(&format!("%{:02X}", b));
+        }
+    }
+    encoded
+}
+
+fn decode(input: &str, plus_as_space: bool) -> Cow<'_, str> {
+    if !input
+        .bytes()
+        .any(|b| b == b'%' || (plus_as_space && b == b'+'))
+    {
+        return Cow::Borrowed(input);
+    }
+
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' if plus_as_space => decoded.push(b' '),
+            b'%' if i + 2 < bytes.len() => {
+                let hi = char::from(bytes[i + 1]).to_digit(16);
+                let lo = char::from(bytes[i + 2]).to_digit(16);
+                match (hi, lo) {
+                    (Some(hi), Some(lo)) => {
+                        decoded.push((hi * 16 + lo) as u8);
+                        i += 2;
+                    }
+                    _ => decoded.push(b'%'),
+                }
+            }
+            b => decoded.push(b),
+        }
+        i += 1;
+    }
+    match String::from_utf8(decoded) {
+        Ok(decoded) => Cow::Owned(decoded),
+        Err(e) => Cow::Owned(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decode_percent_uses_path_semantics() {
+        assert_eq!(decode_percent("/hello%20world"), "/hello world");
+        assert_eq!(decode_percent("/files/a%2Fb"), "/files/a/b");
+        assert_eq!(decode_percent("/a+b"), "/a+b");
+        assert_eq!(decode_percent("/caf%C3%A9"), "/café");
+    }
+
+    #[test]
+    fn decode_percent_borrows_and_keeps_malformed_escapes() {
+        assert!(matches!(decode_percent("/plain/path"), Cow::Borrowed(_)));
+        assert_eq!(decode_percent("/100%"), "/100%");
+        assert_eq!(decode_percent("/%zz%4"), "/%zz%4");
+        assert_eq!(decode_percent("/%FF"), "/\u{FFFD}");
+    }
+
+    #[test]
+    fn query_components_decode_plus_as_space() {
+        assert_eq!(decode_query_component("a+b%26c"), "a b&c");
+        assert_eq!(encode_query_component("a b&c"), "a%20b%26c");
+    }
+}
@@ -1,9 +1,52 @@
+use crate::cookie::Cookie;
+use crate::error::RinError;
 use http::{StatusCode, HeaderMap};
+use serde::Serialize;
 use bytes::Bytes;
-use std::convert::Into;
+use std::convert::{Infallible, Into};
+use std::fmt;
+use std::time::SystemTime;
+
+/// 为基础 MIME 类型拼接 `charset` 参数，例如 `text/html; charset=utf-8`。
+///
+/// 所有输出文本的响应都应通过它声明字符集，避免浏览器按错误编码解析产生乱码。
+/// 注意 `application/json` 规定始终为 UTF-8，不应附加 `charset` 参数。
+pub fn mime_with_charset(base: &str, charset: &str) -> String {
+    format!("{}; charset={}", base, charset)
+}
+
+/// 返回 `base; charset=utf-8` 形式的 Content-Type 头的值。
+///
+/// 设置文本响应的 Content-Type 时应统一使用它，而不是自行拼接字符串。
+pub fn utf8_content_type(base: &str) -> http::header::HeaderValue {
+    http::header::HeaderValue::from_str(&mime_with_charset(base, "utf-8"))
+        .expect("MIME 类型应当是合法的头部值")
+}
+
+/// 该状态码的响应是否必须携带 `Location` 头（301、302、303、307、308）。
+///
+/// 304 Not Modified 和 305 Use Proxy 虽然也是 3xx，但不表示跳转到新位置。
+pub fn requires_location(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 301 | 302 | 303 | 307 | 308)
+}
+
+/// 该状态码的响应是否允许携带响应体；1xx、204 和 304 不允许。
+pub fn allows_body(status: StatusCode) -> bool {
+    !(status.is_informational() || matches!(status.as_u16(), 204 | 304))
+}
+
+/// 该状态码的响应是否默认可缓存（RFC 9110 第 15.1 节），即没有显式缓存指令时缓存也可以启发式地保存。
+///
+/// RFC 9110 的列表包含 204，但 204 没有可缓存的内容，这里按不可缓存处理。
+pub fn is_cacheable(status: StatusCode) -> bool {
+    matches!(
+        status.as_u16(),
+        200 | 203 | 206 | 300 | 301 | 308 | 404 | 405 | 410 | 414 | 501
+    )
+}
 
 /// 表示一个高层次的 HTTP 响应。
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Response {
     pub status: StatusCode,
     pub headers: HeaderMap,
@@ -20,6 +63,54 @@ impl Response {
         }
     }
 
+    /// 创建以原因短语（例如 `Not Found`）为纯文本响应体的响应，适合快速返回错误。
+    ///
+    /// 没有标准原因短语的状态码使用数字本身作为响应体；
+    /// 1xx、204 和 304 不允许携带响应体，返回空响应体且不设置 `Content-Length`。
+    pub fn from_status(status: StatusCode) -> Self {
+        if !allows_body(status) {
+            return Response::new().with_status(status);
+        }
+        let body = status.canonical_reason().unwrap_or(status.as_str());
+        let mut response = body.to_string().into_response().with_status(status);
+        response
+            .headers
+            .insert(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(body.len()));
+        response
+    }
+
+    /// 创建跳转到 `location` 的重定向响应，可继续链式设置响应体。
+    ///
+    /// # Errors
+    /// `status` 不是 301、302、303、307、308 之一，或 `location` 不是合法的头部值时，
+    /// 返回 `RinError::Internal`。
+    pub fn redirect(location: &str, status: StatusCode) -> Result<Self, RinError> {
+        if !requires_location(status) {
+            return Err(RinError::Internal(format!("{} is not a redirect status", status)));
+        }
+        let location = http::header::HeaderValue::from_str(location)
+            .map_err(|_| RinError::Internal(format!("Invalid redirect location: {:?}", location)))?;
+        Ok(Response::new()
+            .with_status(status)
+            .with_header(http::header::LOCATION, location))
+    }
+
+    /// 创建 308 Permanent Redirect 响应，客户端会保留原请求的方法和请求体。
+    ///
+    /// # Errors
+    /// `location` 不是合法的头部值时返回 `RinError::Internal`。
+    pub fn redirect_permanent(location: &str) -> Result<Self, RinError> {
+        Self::redirect(location, StatusCode::PERMANENT_REDIRECT)
+    }
+
+    /// 创建 307 Temporary Redirect 响应，客户端会保留原请求的方法和请求体。
+    ///
+    /// # Errors
+    /// `location` 不是合法的头部值时返回 `RinError::Internal`。
+    pub fn redirect_temporary(location: &str) -> Result<Self, RinError> {
+        Self::redirect(location, StatusCode::TEMPORARY_REDIRECT)
+    }
+
     /// 设置响应状态码。
     pub fn with_status(mut self, status: StatusCode) -> Self {
         self.status = status;
@@ -38,6 +129,47 @@ impl Response {
         self
     }
 
+    /// 追加响应头，保留同名的已有值（例如多个 `Set-Cookie`）。
+    pub fn with_appended_header(mut self, key: http::header::HeaderName, value: http::header::HeaderValue) -> Self {
+        self.append_header(key, value);
+        self
+    }
+
+    /// 追加响应头，保留同名的已有值。
+    pub fn append_header(&mut self, key: http::header::HeaderName, value: http::header::HeaderValue) {
+        self.headers.append(key, value);
+    }
+
+    /// 以当前时间设置 `Date` 响应头（IMF-fixdate 格式）。
+    ///
+    /// HTTP/1.1 源服务器应当在响应中携带 `Date`；已有的 `Date` 会被覆盖。
+    pub fn with_date(self) -> Self {
+        let value = http::header::HeaderValue::from_str(&crate::date::httpdate(SystemTime::now()))
+            .expect("HTTP 日期应当是合法的头部值");
+        self.with_header(http::header::DATE, value)
+    }
+
+    /// 追加一个 `Set-Cookie` 响应头，已有的 Cookie 会被保留。
+    ///
+    /// 适合在没有 `Context` 的场景（例如中间件或测试）中构造响应。
+    ///
+    /// # Errors
+    /// Cookie 无法转换为合法的头部值时返回 `RinError::Internal`。
+    pub fn with_cookie(mut self, cookie: Cookie) -> Result<Self, RinError> {
+        self.set_cookie(cookie)?;
+        Ok(self)
+    }
+
+    /// 追加一个 `Set-Cookie` 响应头。
+    ///
+    /// # Errors
+    /// Cookie 无法转换为合法的头部值时返回 `RinError::Internal`，响应保持不变。
+    pub fn set_cookie(&mut self, cookie: Cookie) -> Result<(), RinError> {
+        let value = cookie.to_header_value()?;
+        self.append_header(http::header::SET_COOKIE, value);
+        Ok(())
+    }
+
     /// 获取响应头可变引用。
     pub fn headers_mut(&mut self) -> &mut HeaderMap {
         &mut self.headers
@@ -52,6 +184,90 @@ impl Response {
     pub fn set_status(&mut self, status: StatusCode) {
         self.status = status;
     }
+
+    /// 在发送前修正 `Content-Length`，保证它与响应体一致。
+    ///
+    /// 普通响应的 `Content-Length` 总是按当前响应体重新计算；
+    /// 带有 `Transfer-Encoding` 的响应移除 `Content-Length`；
+    /// 1xx 和 204 响应不允许有响应体，两者都会被清除；
+    /// 304 响应清空响应体，但保留处理函数给出的 `Content-Length`（它描述的是原资源）。
+    pub fn finalize(&mut self) {
+        if !allows_body(self.status) {
+            self.body = Bytes::new();
+            if self.status != StatusCode::NOT_MODIFIED {
+                self.headers.remove(http::header::CONTENT_LENGTH);
+            }
+        } else if self.headers.contains_key(http::header::TRANSFER_ENCODING) {
+            self.headers.remove(http::header::CONTENT_LENGTH);
+        } else {
+            self.headers
+                .insert(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(self.body.len()));
+        }
+    }
+
+    /// 使用 gzip 压缩响应体，设置 `Content-Encoding: gzip` 并重新计算 `Content-Length`。
+    ///
+    /// 响应体为空或已设置 `Content-Encoding` 时保持原样。
+    /// 调用方应先确认客户端的 `Accept-Encoding` 接受 gzip。
+    #[cfg(feature = "gzip")]
+    pub fn gzip(mut self) -> Self {
+        use flate2::{Compression, write::GzEncoder};
+        use std::io::Write;
+
+        if self.body.is_empty() || self.headers.contains_key(http::header::CONTENT_ENCODING) {
+            return self;
+        }
+
+        let mut encoder = GzEncoder::new(Vec::with_capacity(self.body.len() / 2), Compression::default());
+        let compressed = encoder
+            .write_all(&self.body)
+            .and_then(|()| encoder.finish())
+            .expect("写入内存缓冲区不会失败");
+        self.body = Bytes::from(compressed);
+        self.headers.insert(
+            http::header::CONTENT_ENCODING,
+            http::header::HeaderValue::from_static("gzip"),
+        );
+        self.headers
+            .insert(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(self.body.len()));
+        self
+    }
+
+    /// 将处理结果转换为 JSON 响应。
+    ///
+    /// `Ok` 序列化为 200 的 JSON 响应；`Err` 转换为 `RinError` 后，
+    /// 以对应的状态码和 `{"error": "..."}` 形式的 JSON 返回。
+    /// 序列化失败时返回 500。
+    pub fn from_json_result<T: Serialize, E: Into<RinError>>(result: Result<T, E>) -> Response {
+        let (status, body) = match result.map_err(Into::into).and_then(|value| {
+            serde_json::to_vec(&value).map_err(|e| RinError::Internal(e.to_string()))
+        }) {
+            Ok(body) => (StatusCode::OK, body),
+            Err(err) => {
+                log::debug!("Rendering error as JSON response: {}", err);
+                let body = serde_json::json!({ "error": err.public_message() });
+                (err.status_code(), body.to_string().into_bytes())
+            }
+        };
+        Response::new()
+            .with_status(status)
+            .with_body(body)
+            .with_header(
+                http::header::CONTENT_TYPE,
+                http::header::HeaderValue::from_static("application/json"),
+            )
+    }
+}
+
+// 手动实现 Debug，隐藏 Set-Cookie 等敏感头部的取值
+impl fmt::Debug for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Response")
+            .field("status", &self.status)
+            .field("headers", &crate::request::RedactedHeaders::new(&self.headers))
+            .field("body", &self.body)
+            .finish()
+    }
 }
 
 impl Default for Response {
@@ -79,19 +295,17 @@ impl IntoResponse for Response {
 
 impl IntoResponse for String {
     fn into_response(self) -> Response {
-        Response::new().with_body(self).with_header(
-            http::header::CONTENT_TYPE,
-            http::header::HeaderValue::from_static("text/plain; charset=utf-8"),
-        )
+        Response::new()
+            .with_body(self)
+            .with_header(http::header::CONTENT_TYPE, utf8_content_type("text/plain"))
     }
 }
 
 impl IntoResponse for &'static str {
     fn into_response(self) -> Response {
-        Response::new().with_body(self).with_header(
-            http::header::CONTENT_TYPE,
-            http::header::HeaderValue::from_static("text/plain; charset=utf-8"),
-        )
+        Response::new()
+            .with_body(self)
+            .with_header(http::header::CONTENT_TYPE, utf8_content_type("text/plain"))
     }
 }
 
@@ -101,6 +315,32 @@ impl IntoResponse for StatusCode {
     }
 }
 
+/// 以指定状态码返回任意可转换为响应的内容，例如 `WithStatus(StatusCode::CREATED, body)`。
+#[derive(Debug, Clone)]
+pub struct WithStatus<T>(pub StatusCode, pub T);
+
+impl<T: IntoResponse> IntoResponse for WithStatus<T> {
+    fn into_response(self) -> Response {
+        self.1.into_response().with_status(self.0)
+    }
+}
+
+// 以纯文本形式渲染错误，状态码由错误类型决定
+impl IntoResponse for RinError {
+    fn into_response(self) -> Response {
+        let mut response = self.public_message().into_response();
+        response.set_status(self.status_code());
+        response
+    }
+}
+
+// 不可能构造出 Infallible 的值，因此这个分支永远不会执行
+impl IntoResponse for Infallible {
+    fn into_response(self) -> Response {
+        match self {}
+    }
+}
+
 // TODO: 可以为 Result<T, E> 实现 IntoResponse，以便处理函数直接返回 Result
 // impl<T: IntoResponse, E: Into<Error>> IntoResponse for Result<T, E> {
 //     fn into_response(self) -> Response {
@@ -115,4 +355,214 @@ impl IntoResponse for StatusCode {
 //             }
 //         }
 //     }
-// }
\ No newline at end of file
+// }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mime_with_charset_appends_parameter() {
+        assert_eq!(mime_with_charset("text/html", "utf-8"), "text/html; charset=utf-8");
+    }
+
+    #[test]
+    fn requires_location_only_for_redirects() {
+        assert!(requires_location(StatusCode::MOVED_PERMANENTLY));
+        assert!(requires_location(StatusCode::PERMANENT_REDIRECT));
+        assert!(!requires_location(StatusCode::NOT_MODIFIED));
+        assert!(!requires_location(StatusCode::USE_PROXY));
+        assert!(!requires_location(StatusCode::OK));
+    }
+
+    #[test]
+    fn from_status_uses_reason_phrase() {
+        let response = Response::from_status(StatusCode::NOT_FOUND);
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.body, Bytes::from_static(b"Not Found"));
+        assert_eq!(response.headers[http::header::CONTENT_LENGTH], "9");
+        assert_eq!(response.headers[http::header::CONTENT_TYPE], "text/plain; charset=utf-8");
+
+        let response = Response::from_status(StatusCode::from_u16(599).unwrap());
+        assert_eq!(response.body, Bytes::from_static(b"599"));
+    }
+
+    #[test]
+    fn from_status_leaves_bodiless_statuses_empty() {
+        for status in [StatusCode::NO_CONTENT, StatusCode::NOT_MODIFIED, StatusCode::CONTINUE] {
+            let response = Response::from_status(status);
+            assert_eq!(response.status, status);
+            assert!(response.body.is_empty());
+            assert!(!response.headers.contains_key(http::header::CONTENT_LENGTH));
+            assert!(!response.headers.contains_key(http::header::CONTENT_TYPE));
+        }
+    }
+
+    #[test]
+    fn redirect_sets_status_and_location() {
+        let response = Response::redirect("/login", StatusCode::SEE_OTHER).unwrap().with_body("see /login");
+        assert_eq!(response.status, StatusCode::SEE_OTHER);
+        assert_eq!(response.headers[http::header::LOCATION], "/login");
+        assert_eq!(response.body, Bytes::from_static(b"see /login"));
+
+        let response = Response::redirect_permanent("https://example.com/new").unwrap();
+        assert_eq!(response.status, StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers[http::header::LOCATION], "https://example.com/new");
+        assert_eq!(Response::redirect_temporary("/tmp").unwrap().status, StatusCode::TEMPORARY_REDIRECT);
+
+        assert!(matches!(Response::redirect("/", StatusCode::NOT_MODIFIED), Err(RinError::Internal(_))));
+        assert!(matches!(Response::redirect("/a\nb", StatusCode::FOUND), Err(RinError::Internal(_))));
+    }
+
+    #[test]
+    fn allows_body_and_is_cacheable() {
+        assert!(allows_body(StatusCode::OK));
+        assert!(is_cacheable(StatusCode::OK));
+
+        assert!(!allows_body(StatusCode::NO_CONTENT));
+        assert!(!is_cacheable(StatusCode::NO_CONTENT));
+        assert!(!allows_body(StatusCode::NOT_MODIFIED));
+        assert!(!allows_body(StatusCode::CONTINUE));
+
+        assert!(is_cacheable(StatusCode::GONE));
+        assert!(!is_cacheable(StatusCode::CREATED));
+        assert!(!is_cacheable(StatusCode::INTERNAL_SERVER_ERROR));
+    }
+
+    #[test]
+    fn appended_headers_keep_existing_values() {
+        let response = Response::new()
+            .with_appended_header(http::header::SET_COOKIE, http::header::HeaderValue::from_static("a=1"))
+            .with_appended_header(http::header::SET_COOKIE, http::header::HeaderValue::from_static("b=2"));
+
+        let cookies: Vec<_> = response.headers.get_all(http::header::SET_COOKIE).iter().collect();
+        assert_eq!(cookies, ["a=1", "b=2"]);
+    }
+
+    #[test]
+    fn with_cookie_appends_set_cookie_headers() {
+        let mut response = Response::new()
+            .with_cookie(Cookie::new("a", "1").with_path("/"))
+            .and_then(|response| response.with_cookie(Cookie::new("b", "2").with_http_only(true)))
+            .unwrap();
+        let err = response.set_cookie(Cookie::new("bad\nname", "value")).unwrap_err();
+        assert!(matches!(err, RinError::Internal(_)));
+
+        let cookies: Vec<_> = response.headers.get_all(http::header::SET_COOKIE).iter().collect();
+        assert_eq!(cookies, ["a=1; Path=/", "b=2; HttpOnly"]);
+    }
+
+    #[test]
+    fn finalize_corrects_content_length() {
+        let mut response = Response::new()
+            .with_body("hello")
+            .with_header(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(42));
+        response.finalize();
+        assert_eq!(response.headers[http::header::CONTENT_LENGTH], "5");
+
+        let mut response = Response::new()
+            .with_status(StatusCode::NO_CONTENT)
+            .with_body("stale")
+            .with_header(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(5));
+        response.finalize();
+        assert!(response.body.is_empty());
+        assert!(!response.headers.contains_key(http::header::CONTENT_LENGTH));
+
+        let mut response = Response::new()
+            .with_status(StatusCode::CONTINUE)
+            .with_header(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(5));
+        response.finalize();
+        assert!(!response.headers.contains_key(http::header::CONTENT_LENGTH));
+
+        let mut response = Response::new()
+            .with_status(StatusCode::NOT_MODIFIED)
+            .with_body("stale")
+            .with_header(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(1024));
+        response.finalize();
+        assert!(response.body.is_empty());
+        assert_eq!(response.headers[http::header::CONTENT_LENGTH], "1024");
+
+        let mut response = Response::new()
+            .with_body("chunked")
+            .with_header(http::header::TRANSFER_ENCODING, http::header::HeaderValue::from_static("chunked"))
+            .with_header(http::header::CONTENT_LENGTH, http::header::HeaderValue::from(7));
+        response.finalize();
+        assert!(!response.headers.contains_key(http::header::CONTENT_LENGTH));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_compresses_body_round_trip() {
+        use std::io::Read;
+
+        let original = "hello rin ".repeat(100);
+        let response = Response::new().with_body(original.clone()).gzip();
+        assert_eq!(response.headers[http::header::CONTENT_ENCODING], "gzip");
+        assert_eq!(response.headers[http::header::CONTENT_LENGTH], response.body.len().to_string().as_str());
+        assert!(response.body.len() < original.len());
+
+        let mut decompressed = String::new();
+        flate2::read::GzDecoder::new(&response.body[..])
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, original);
+
+        let encoded = Response::new()
+            .with_body("already")
+            .with_header(http::header::CONTENT_ENCODING, http::header::HeaderValue::from_static("br"))
+            .gzip();
+        assert_eq!(encoded.body, Bytes::from_static(b"already"));
+        assert!(Response::new().gzip().headers.is_empty());
+    }
+
+    #[test]
+    fn from_json_result_serializes_ok() {
+        let response = Response::from_json_result(Ok::<_, RinError>(serde_json::json!({ "id": 7 })));
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.headers.get(http::header::CONTENT_TYPE).unwrap(), "application/json");
+        assert_eq!(response.body, Bytes::from_static(br#"{"id":7}"#));
+    }
+
+    #[test]
+    fn from_json_result_maps_errors() {
+        let response = Response::from_json_result(Err::<(), _>(RinError::NotFound));
+        assert_eq!(response.status, StatusCode::NOT_FOUND);
+        assert_eq!(response.body, Bytes::from_static(br#"{"error":"Not Found"}"#));
+
+        let response = Response::from_json_result(Err::<(), _>(RinError::Internal("db down".into())));
+        assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(response.body, Bytes::from_static(br#"{"error":"Internal Server Error"}"#));
+    }
+
+    #[test]
+    fn with_date_sets_current_http_date() {
+        let response = Response::new().with_date();
+        let date = response.headers[http::header::DATE].to_str().unwrap();
+        let parsed = crate::date::parse_httpdate(date).unwrap();
+        let drift = SystemTime::now().duration_since(parsed).unwrap();
+        assert!(drift.as_secs() <= 1, "{}", date);
+    }
+
+    #[test]
+    fn with_status_overrides_status_and_keeps_body() {
+        let response = WithStatus(StatusCode::CREATED, String::from("created")).into_response();
+        assert_eq!(response.status, StatusCode::CREATED);
+        assert_eq!(response.body, Bytes::from_static(b"created"));
+        assert_eq!(
+            response.headers.get(http::header::CONTENT_TYPE).unwrap(),
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[test]
+    fn text_responses_declare_utf8() {
+        let from_string = String::from("hello").into_response();
+        let from_str = "hello".into_response();
+        for response in [from_string, from_str] {
+            assert_eq!(
+                response.headers.get(http::header::CONTENT_TYPE).unwrap(),
+                "text/plain; charset=utf-8"
+            );
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 服务器级别的优雅停机信号。
+///
+/// 服务器持有一份并在开始优雅停机时调用 `trigger`，同一信号的克隆会注入到每个请求的 `Context` 中。
+/// SSE、流式响应等长时间运行的处理函数可以据此尽快收尾。
+/// 它表示整个服务器即将停止，与单个请求被取消（例如客户端断开）无关。
+#[derive(Debug, Clone, Default)]
+pub struct ShutdownSignal {
+    triggered: Arc<AtomicBool>,
+}
+
+impl ShutdownSignal {
+    /// 创建一个尚未触发的信号。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 标记服务器开始停机，所有克隆都会观察到。
+    pub fn trigger(&self) {
+        self.triggered.store(true, Ordering::Release);
+    }
+
+    /// 服务器是否已开始停机。
+    pub fn is_triggered(&self) -> bool {
+        self.triggered.load(Ordering::Acquire)
+    }
+}
@@ -0,0 +1,49 @@
+use http::StatusCode;
+use serde::de::{Error, Unexpected};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// 以数字形式序列化 `StatusCode`，配合 `#[serde(with = "rin_core::status_serde")]` 使用。
+///
+/// 适用于错误信封等需要在 JSON 中携带数字状态码的场景。
+pub fn serialize<S: Serializer>(status: &StatusCode, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u16(status.as_u16())
+}
+
+/// 从数字反序列化 `StatusCode`，不在 100-999 范围内的值会被拒绝。
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<StatusCode, D::Error> {
+    let code = u16::deserialize(deserializer)?;
+    StatusCode::from_u16(code).map_err(|_| {
+        D::Error::invalid_value(Unexpected::Unsigned(code.into()), &"an HTTP status code")
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Envelope {
+        #[serde(with = "crate::status_serde")]
+        status: StatusCode,
+    }
+
+    #[test]
+    fn serializes_as_number() {
+        let envelope = Envelope {
+            status: StatusCode::NOT_FOUND,
+        };
+        assert_eq!(
+            serde_json::to_string(&envelope).unwrap(),
+            r#"{"status":404}"#
+        );
+    }
+
+    #[test]
+    fn deserializes_and_validates() {
+        let envelope: Envelope = serde_json::from_str(r#"{"status":404}"#).unwrap();
+        assert_eq!(envelope.status, StatusCode::NOT_FOUND);
+        assert!(serde_json::from_str::<Envelope>(r#"{"status":42}"#).is_err());
+        assert!(serde_json::from_str::<Envelope>(r#"{"status":"404"}"#).is_err());
+    }
+}
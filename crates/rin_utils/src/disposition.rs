@@ -0,0 +1,206 @@
+use rin_core::RinError;
+
+/// 解析后的 `Content-Disposition` 头。
+///
+/// 同时用于 multipart 表单字段（`form-data; name="..."`）和下载响应（`attachment; filename="..."`）。
+/// RFC 5987 扩展参数（如 `filename*=UTF-8''%E4%BD%A0.txt`）会在解析时解码，
+/// 并以带 `*` 的参数名保存。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentDisposition {
+    disposition: String,
+    params: Vec<(String, String)>,
+}
+
+impl ContentDisposition {
+    /// 解析 `Content-Disposition` 头的值。
+    ///
+    /// # Errors
+    /// 类型缺失、参数格式错误或扩展参数无法解码时返回 `RinError::BadRequest`。
+    pub fn parse(value: &str) -> Result<Self, RinError> {
+        let mut parts = split_params(value).into_iter();
+        let disposition = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+        if disposition.is_empty() {
+            return Err(RinError::BadRequest(
+                "Missing Content-Disposition type".to_string(),
+            ));
+        }
+
+        let mut params = Vec::new();
+        for part in parts {
+            let part = part.trim();
+            if part.is_empty() {
+                continue;
+            }
+            let (name, raw) = part.split_once('=').ok_or_else(|| {
+                RinError::BadRequest(format!("Invalid Content-Disposition parameter: {}", part))
+            })?;
+            let name = name.trim().to_ascii_lowercase();
+            let raw = raw.trim();
+            let value = if name.ends_with('*') {
+                decode_ext_value(raw)?
+            } else {
+                unquote(raw)
+            };
+            params.push((name, value));
+        }
+
+        Ok(ContentDisposition {
+            disposition,
+            params,
+        })
+    }
+
+    /// 类型，统一为小写，例如 `form-data`、`attachment`、`inline`。
+    pub fn disposition(&self) -> &str {
+        &self.disposition
+    }
+
+    /// 是否为 `attachment`。
+    pub fn is_attachment(&self) -> bool {
+        self.disposition == "attachment"
+    }
+
+    /// 是否为 `form-data`。
+    pub fn is_form_data(&self) -> bool {
+        self.disposition == "form-data"
+    }
+
+    /// 按名称（不区分大小写）获取参数。扩展参数需要带上 `*`，例如 `filename*`。
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// multipart 字段名。
+    pub fn name(&self) -> Option<&str> {
+        self.param("name")
+    }
+
+    /// 文件名，优先使用 RFC 5987 编码的 `filename*`。
+    pub fn filename(&self) -> Option<&str> {
+        self.param("filename*").or_else(|| self.param("filename"))
+    }
+}
+
+// 按 `;` 拆分参数，忽略引号内的分号
+fn split_params(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut escaped = false;
+    for (i, c) in value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_quotes => escaped = true,
+            '"' => in_quotes = !in_quotes,
+            ';' if !in_quotes => {
+                parts.push(&value[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+// 去掉 quoted-string 两侧的引号并处理反斜杠转义
+fn unquote(value: &str) -> String {
+    match value
+        .strip_prefix('"')
+        .and_then(|inner| inner.strip_suffix('"'))
+    {
+        Some(inner) => {
+            let mut unquoted = String::with_capacity(inner.len());
+            let mut chars = inner.chars();
+            while let Some(c) = chars.next() {
+                if c == '\\' {
+                    if let Some(next) = chars.next() {
+                        unquoted.push(next);
+                    }
+                } else {
+                    unquoted.push(c);
+                }
+            }
+            unquoted
+        }
+        None => value.to_string(),
+    }
+}
+
+// 解码 RFC 5987 扩展值：charset'language'percent-encoded
+fn decode_ext_value(value: &str) -> Result<String, RinError> {
+    let invalid = || RinError::BadRequest(format!("Invalid extended parameter value: {}", value));
+    let mut parts = value.splitn(3, '\'');
+    let (charset, _language, encoded) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(charset), Some(language), Some(encoded)) => (charset, language, encoded),
+        _ => return Err(invalid()),
+    };
+
+    let bytes = percent_decode(encoded).ok_or_else(invalid)?;
+    if charset.eq_ignore_ascii_case("utf-8") {
+        String::from_utf8(bytes).map_err(|_| invalid())
+    } else if charset.eq_ignore_ascii_case("iso-8859-1") {
+        Ok(bytes.into_iter().map(char::from).collect())
+    } else {
+        Err(RinError::BadRequest(format!(
+            "Unsupported charset in extended parameter: {}",
+            charset
+        )))
+    }
+}
+
+// 严格的百分号解码，`+` 不作特殊处理，格式错误的转义返回 None
+fn percent_decode(s: &str) -> Option<Vec<u8>> {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3)?;
+            if !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                return None;
+            }
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(decoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_form_data_with_plain_filename() {
+        let cd = ContentDisposition::parse(r#"form-data; name="avatar"; filename="my; photo.png""#)
+            .unwrap();
+        assert!(cd.is_form_data());
+        assert_eq!(cd.name(), Some("avatar"));
+        assert_eq!(cd.filename(), Some("my; photo.png"));
+    }
+
+    #[test]
+    fn prefers_rfc5987_encoded_filename() {
+        let cd = ContentDisposition::parse(
+            "Attachment; filename=\"fallback.txt\"; filename*=UTF-8''%E4%BD%A0%E5%A5%BD%20rin.txt",
+        )
+        .unwrap();
+        assert!(cd.is_attachment());
+        assert_eq!(cd.filename(), Some("你好 rin.txt"));
+        assert_eq!(cd.param("filename"), Some("fallback.txt"));
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        assert!(ContentDisposition::parse("").is_err());
+        assert!(ContentDisposition::parse("attachment; filename*=%E4").is_err());
+        assert!(ContentDisposition::parse("attachment; filename*=UTF-8''%G1").is_err());
+    }
+}
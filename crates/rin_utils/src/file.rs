@@ -0,0 +1,300 @@
+use async_trait::async_trait;
+use rin_core::response::utf8_content_type;
+use rin_core::{Context, HeaderValue, RinError, StatusCode, header};
+use std::io::SeekFrom;
+use std::path::Path;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+/// `stream_file` 默认允许读入内存的最大字节数：64 MiB。
+pub const DEFAULT_MAX_FILE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 文件响应配置。
+///
+/// 存入 `Context` 后（`ctx.set(FileConfig { .. })`）对 `stream_file` 生效；
+/// 未设置时使用 `FileConfig::default()`。
+/// 响应体是一整块 `Bytes`，所选内容会完整读入内存，此上限用于避免大文件耗尽内存。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileConfig {
+    /// 单次响应允许读入内存的最大字节数，对 `Range` 请求按所选范围计算。
+    pub max_bytes: u64,
+}
+
+impl Default for FileConfig {
+    fn default() -> Self {
+        FileConfig {
+            max_bytes: DEFAULT_MAX_FILE_BYTES,
+        }
+    }
+}
+
+/// 扩展 `Context` 以提供文件响应方法。
+#[async_trait]
+pub trait ContextFileExt {
+    /// 将文件内容作为响应发送，支持单个 `Range` 请求。
+    ///
+    /// 根据扩展名设置 `Content-Type`，并始终设置 `Accept-Ranges: bytes`。
+    /// 请求带有可满足的 `Range` 时，只定位并读取所请求的字节，响应 206；
+    /// 范围不可满足时响应 416；多段或无法解析的 `Range` 会被忽略，返回完整文件。
+    ///
+    /// 所选内容会一次性读入内存作为响应体，并不是真正的流式传输，
+    /// 因此大小受 `FileConfig::max_bytes` 限制；客户端仍可以用 `Range` 分段获取更大的文件。
+    ///
+    /// # Errors
+    /// 文件不存在时返回 `RinError::NotFound`；
+    /// 所选内容超过 `FileConfig::max_bytes` 或发生其他 I/O 错误（包括无权限）时返回 `RinError::Internal`。
+    async fn stream_file(&mut self, path: &Path) -> Result<(), RinError>;
+}
+
+#[async_trait]
+impl ContextFileExt for Context {
+    async fn stream_file(&mut self, path: &Path) -> Result<(), RinError> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let metadata = file.metadata().await?;
+        if !metadata.is_file() {
+            return Err(RinError::NotFound);
+        }
+        let len = metadata.len();
+
+        let range = self
+            .headers()
+            .get(header::RANGE)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| parse_range(value, len));
+
+        let max_bytes = self.get::<FileConfig>().copied().unwrap_or_default().max_bytes;
+        let selected = match range {
+            Some(Range::Satisfiable(start, end)) => end - start + 1,
+            Some(Range::Unsatisfiable) => 0,
+            Some(Range::Ignored) | None => len,
+        };
+        if selected > max_bytes {
+            log::warn!(
+                "{} bytes of {} exceed the file response limit of {} bytes",
+                selected,
+                path.display(),
+                max_bytes
+            );
+            return Err(RinError::Internal(format!(
+                "file response of {} bytes exceeds the limit of {} bytes",
+                selected, max_bytes
+            )));
+        }
+
+        self.headers_mut()
+            .insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+        self.headers_mut()
+            .insert(header::CONTENT_TYPE, content_type_for(path));
+
+        let (start, end) = match range {
+            Some(Range::Satisfiable(start, end)) => {
+                let content_range = format!("bytes {}-{}/{}", start, end, len);
+                self.headers_mut().insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&content_range)
+                        .expect("Content-Range 应当是合法的头部值"),
+                );
+                self.set_status(StatusCode::PARTIAL_CONTENT);
+                (start, end)
+            }
+            Some(Range::Unsatisfiable) => {
+                let content_range = format!("bytes */{}", len);
+                self.headers_mut().insert(
+                    header::CONTENT_RANGE,
+                    HeaderValue::from_str(&content_range)
+                        .expect("Content-Range 应当是合法的头部值"),
+                );
+                self.set_status(StatusCode::RANGE_NOT_SATISFIABLE);
+                self.set_body(Vec::new());
+                return Ok(());
+            }
+            Some(Range::Ignored) | None if len == 0 => {
+                self.set_body(Vec::new());
+                return Ok(());
+            }
+            Some(Range::Ignored) | None => (0, len - 1),
+        };
+
+        let mut body = Vec::with_capacity((end - start + 1) as usize);
+        file.seek(SeekFrom::Start(start)).await?;
+        file.take(end - start + 1).read_to_end(&mut body).await?;
+        self.set_body(body);
+        Ok(())
+    }
+}
+
+enum Range {
+    // 闭区间 [start, end]
+    Satisfiable(u64, u64),
+    Unsatisfiable,
+    Ignored,
+}
+
+// 解析 `bytes=a-b`、`bytes=a-`、`bytes=-n` 形式的单个范围
+fn parse_range(value: &str, len: u64) -> Range {
+    let Some(spec) = value.trim().strip_prefix("bytes=") else {
+        return Range::Ignored;
+    };
+    if spec.contains(',') {
+        return Range::Ignored;
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return Range::Ignored;
+    };
+
+    let (start, end) = match (first.trim(), last.trim()) {
+        ("", "") => return Range::Ignored,
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return Range::Unsatisfiable,
+            Ok(suffix) => (len.saturating_sub(suffix), len.saturating_sub(1)),
+            Err(_) => return Range::Ignored,
+        },
+        (first, last) => {
+            let Ok(start) = first.parse::<u64>() else {
+                return Range::Ignored;
+            };
+            let end = match last {
+                "" => len.saturating_sub(1),
+                last => match last.parse::<u64>() {
+                    Ok(end) if end >= start => end.min(len.saturating_sub(1)),
+                    _ => return Range::Ignored,
+                },
+            };
+            (start, end)
+        }
+    };
+
+    if len == 0 || start >= len {
+        Range::Unsatisfiable
+    } else {
+        Range::Satisfiable(start, end)
+    }
+}
+
+fn content_type_for(path: &Path) -> HeaderValue {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase());
+    let mime = match extension.as_deref() {
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js" | "mjs") => "text/javascript",
+        Some("txt") => "text/plain",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("webp") => "image/webp",
+        Some("mp4") => "video/mp4",
+        Some("webm") => "video/webm",
+        Some("mp3") => "audio/mpeg",
+        Some("pdf") => "application/pdf",
+        _ => "application/octet-stream",
+    };
+
+    if mime.starts_with("text/") {
+        utf8_content_type(mime)
+    } else {
+        HeaderValue::from_static(mime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rin_core::Method;
+    use std::path::PathBuf;
+
+    fn temp_file(name: &str, contents: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rin-file-{}-{}", std::process::id(), name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn stream_file_sends_full_file() {
+        let path = temp_file("full.txt", b"hello world");
+        let mut ctx = Context::test(Method::GET, "/file");
+        ctx.stream_file(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let response = ctx.response();
+        assert_eq!(response.status, StatusCode::OK);
+        assert_eq!(response.body, "hello world");
+        assert_eq!(response.headers[header::ACCEPT_RANGES], "bytes");
+        assert_eq!(
+            response.headers[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+    }
+
+    #[tokio::test]
+    async fn stream_file_serves_requested_range() {
+        let path = temp_file("range.bin", b"0123456789");
+        let mut ctx = Context::test(Method::GET, "/file").with_header("range", "bytes=2-5");
+        ctx.stream_file(&path).await.unwrap();
+
+        let response = ctx.response();
+        assert_eq!(response.status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.body, "2345");
+        assert_eq!(response.headers[header::CONTENT_RANGE], "bytes 2-5/10");
+        assert_eq!(
+            response.headers[header::CONTENT_TYPE],
+            "application/octet-stream"
+        );
+
+        let mut ctx = Context::test(Method::GET, "/file").with_header("range", "bytes=-3");
+        ctx.stream_file(&path).await.unwrap();
+        assert_eq!(ctx.response().body, "789");
+
+        let mut ctx = Context::test(Method::GET, "/file").with_header("range", "bytes=10-");
+        ctx.stream_file(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ctx.response().status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(ctx.response().headers[header::CONTENT_RANGE], "bytes */10");
+    }
+
+    #[tokio::test]
+    async fn stream_file_enforces_max_bytes() {
+        let path = temp_file("limit.bin", b"0123456789");
+        let mut ctx = Context::test(Method::GET, "/file");
+        ctx.set(FileConfig { max_bytes: 4 });
+        let err = ctx.stream_file(&path).await.unwrap_err();
+        assert!(matches!(err, RinError::Internal(_)));
+        assert!(ctx.response().headers.get(header::CONTENT_TYPE).is_none());
+
+        // 所选范围不超过上限时仍可分段获取
+        let mut ctx = Context::test(Method::GET, "/file").with_header("range", "bytes=6-");
+        ctx.set(FileConfig { max_bytes: 4 });
+        ctx.stream_file(&path).await.unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(ctx.response().body, "6789");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn stream_file_reports_permission_denied() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = temp_file("secret.txt", b"secret");
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o000)).unwrap();
+        // root 不受文件权限约束，此时无法构造权限错误
+        if std::fs::File::open(&path).is_ok() {
+            std::fs::remove_file(&path).unwrap();
+            return;
+        }
+        let mut ctx = Context::test(Method::GET, "/file");
+        let err = ctx.stream_file(&path).await.unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(err, RinError::Internal(_)));
+    }
+
+    #[tokio::test]
+    async fn stream_file_reports_missing_file() {
+        let mut ctx = Context::test(Method::GET, "/file");
+        let path = std::env::temp_dir().join("rin-file-does-not-exist");
+        let err = ctx.stream_file(&path).await.unwrap_err();
+        assert!(matches!(err, RinError::NotFound));
+    }
+}
@@ -1,14 +1,42 @@
 use async_trait::async_trait;
 use bytes::Bytes;
-use rin_core::{Context, Method, RinError};
+use rin_core::response::utf8_content_type;
+use rin_core::{Context, RinError, StatusCode, header};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use std::fmt::Display;
+
+/// `bind_json` 默认允许的最大 JSON 请求体：2 MiB。
+pub const DEFAULT_MAX_JSON_BYTES: usize = 2 * 1024 * 1024;
+
+/// JSON 绑定配置。
+///
+/// 存入 `Context` 后（`ctx.set(JsonConfig { .. })`）对 `bind_json` 生效，
+/// 通常由服务器或中间件统一注入；未设置时使用 `JsonConfig::default()`。
+/// 这一限制独立于整体请求体大小限制，因为解析 JSON 的开销明显高于读取字节。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JsonConfig {
+    /// 允许解析的最大请求体字节数。
+    pub max_bytes: usize,
+}
+
+impl Default for JsonConfig {
+    fn default() -> Self {
+        JsonConfig {
+            max_bytes: DEFAULT_MAX_JSON_BYTES,
+        }
+    }
+}
 
 #[async_trait]
 pub trait ContextJsonExt {
     /// 从请求体中解析 JSON 到指定的类型。
     ///
+    /// 直接从请求体的字节切片解析，不会复制请求体。
+    /// 由于 `T: DeserializeOwned`，`T` 不能借用请求体中的数据（例如 `&str` 字段）。
+    ///
     /// # Errors
+    /// 如果请求体超过 `JsonConfig::max_bytes`，则返回 `RinError::PayloadTooLarge`；
     /// 如果请求体无法读取或不是有效的 JSON，则返回 `RinError::BadRequest`。
     async fn bind_json<T: DeserializeOwned>(&mut self) -> Result<T, RinError>;
 
@@ -17,43 +45,229 @@ pub trait ContextJsonExt {
     /// # Errors
     /// 如果 `value` 无法序列化为 JSON，则返回 `RinError::Internal`。
     fn json<T: Serialize>(&mut self, value: &T) -> Result<(), RinError>;
+
+    /// 发送 `{"error": message}` 形式的 JSON 错误响应，并设置状态码。
+    ///
+    /// 用于处理函数捕获错误后自行响应的场景。
+    fn json_error(&mut self, status: StatusCode, message: &str);
+
+    /// 根据 `Accept` 头选择表示形式并发送响应。
+    ///
+    /// 客户端偏好 `text/html` 时发送转义后的 `Display` 输出，偏好 `text/plain` 时发送纯文本，
+    /// 其余情况（包括没有可接受的类型）都回退为 JSON。响应会附带 `Vary: Accept`。
+    ///
+    /// # Errors
+    /// 选择 JSON 且 `value` 无法序列化时返回 `RinError::Internal`。
+    fn respond<T: Serialize + Display>(&mut self, value: &T) -> Result<(), RinError>;
 }
 
 #[async_trait]
 impl ContextJsonExt for Context {
     async fn bind_json<T: DeserializeOwned>(&mut self) -> Result<T, RinError> {
-        unimplemented!()
-        // let body_bytes = self.body_bytes().clone(); // Clone for reading
-        // if body_bytes.is_empty() {
-        //     return Err(RinError::BadRequest(
-        //         "Request body is empty for JSON parsing".to_string(),
-        //     ));
-        // }
-        // 
-        // serde_json::from_slice(&body_bytes).map_err(|e| {
-        //     log::warn!("Failed to parse JSON body: {}", e);
-        //     RinError::BadRequest(format!("Invalid JSON format: {}", e))
-        // })
+        // 直接从借用的请求体切片反序列化，避免为大体积 JSON 复制一份请求体。
+        // `T: DeserializeOwned` 保证结果不借用请求体，因此无需克隆。
+        let max_bytes = self
+            .get::<JsonConfig>()
+            .copied()
+            .unwrap_or_default()
+            .max_bytes;
+        let body_bytes = self.body_bytes();
+        if body_bytes.len() > max_bytes {
+            log::warn!(
+                "JSON body of {} bytes exceeds the limit of {} bytes",
+                body_bytes.len(),
+                max_bytes
+            );
+            return Err(RinError::PayloadTooLarge);
+        }
+        if body_bytes.is_empty() {
+            return Err(RinError::BadRequest(
+                "Request body is empty for JSON parsing".to_string(),
+            ));
+        }
+
+        serde_json::from_slice(body_bytes).map_err(|e| {
+            log::warn!("Failed to parse JSON body: {}", e);
+            RinError::BadRequest(format!("Invalid JSON format: {}", e))
+        })
     }
 
     fn json<T: Serialize>(&mut self, value: &T) -> Result<(), RinError> {
-        unimplemented!()
-        // match serde_json::to_vec(value) {
-        //     Ok(json_bytes) => {
-        //         self.response.headers_mut().insert(
-        //             rin_core::header::CONTENT_TYPE,
-        //             rin_core::header::HeaderValue::from_static("application/json"),
-        //         );
-        //         self.response.set_body(Bytes::from(json_bytes));
-        //         Ok(())
-        //     }
-        //     Err(e) => {
-        //         log::error!("Failed to serialize JSON response: {}", e);
-        //         Err(RinError::Internal(format!(
-        //             "Failed to serialize JSON: {}",
-        //             e
-        //         )))
-        //     }
-        // }
+        match serde_json::to_vec(value) {
+            Ok(json_bytes) => {
+                // JSON 规定始终为 UTF-8，因此不附加 charset 参数
+                self.headers_mut().insert(
+                    rin_core::header::CONTENT_TYPE,
+                    rin_core::header::HeaderValue::from_static("application/json"),
+                );
+                self.set_body(Bytes::from(json_bytes));
+                Ok(())
+            }
+            Err(e) => {
+                log::error!("Failed to serialize JSON response: {}", e);
+                Err(RinError::Internal(format!(
+                    "Failed to serialize JSON: {}",
+                    e
+                )))
+            }
+        }
+    }
+
+    fn json_error(&mut self, status: StatusCode, message: &str) {
+        self.set_status(status);
+        self.json(&serde_json::json!({ "error": message }))
+            .expect("字符串组成的 JSON 对象总能序列化");
+    }
+
+    fn respond<T: Serialize + Display>(&mut self, value: &T) -> Result<(), RinError> {
+        self.add_vary(header::ACCEPT);
+        let supported = ["application/json", "text/html", "text/plain"];
+        let (base, body) = match self.preferred_media_type(&supported) {
+            Some("text/html") => ("text/html", escape_html(&value.to_string())),
+            Some("text/plain") => ("text/plain", value.to_string()),
+            _ => return self.json(value),
+        };
+
+        self.headers_mut()
+            .insert(header::CONTENT_TYPE, utf8_content_type(base));
+        self.set_body(body);
+        Ok(())
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rin_core::Method;
+
+    fn context_with_body(body: impl Into<Bytes>) -> Context {
+        Context::test(Method::POST, "/").with_body(body)
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Item {
+        id: u32,
+        name: String,
+    }
+
+    #[test]
+    fn json_error_sets_status_and_body() {
+        let mut ctx = Context::test(Method::POST, "/");
+        ctx.json_error(StatusCode::UNPROCESSABLE_ENTITY, "name is \"required\"");
+
+        let response = ctx.response();
+        assert_eq!(response.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(
+            response.headers.get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(
+            response.body,
+            Bytes::from_static(br#"{"error":"name is \"required\""}"#)
+        );
+    }
+
+    #[tokio::test]
+    async fn bind_json_parses_large_body() {
+        let items: Vec<_> = (0..10_000)
+            .map(|id| serde_json::json!({ "id": id, "name": format!("item-{}", id) }))
+            .collect();
+        let mut ctx = context_with_body(serde_json::to_vec(&items).unwrap());
+
+        let parsed: Vec<Item> = ctx.bind_json().await.unwrap();
+        assert_eq!(parsed.len(), 10_000);
+        assert_eq!(parsed[9_999].id, 9_999);
+        assert_eq!(parsed[9_999].name, "item-9999");
+    }
+
+    #[tokio::test]
+    async fn bind_json_rejects_oversized_bodies() {
+        let mut ctx = context_with_body(r#"{"id": 1, "name": "a"}"#);
+        ctx.set(JsonConfig { max_bytes: 8 });
+        let err = ctx.bind_json::<Item>().await.unwrap_err();
+        assert!(matches!(err, RinError::PayloadTooLarge));
+
+        ctx.set(JsonConfig { max_bytes: 64 });
+        let item: Item = ctx.bind_json().await.unwrap();
+        assert_eq!(item.id, 1);
+    }
+
+    #[tokio::test]
+    async fn bind_json_rejects_empty_and_invalid_bodies() {
+        let empty = Context::test(Method::POST, "/").bind_json::<Vec<Item>>().await.unwrap_err();
+        assert!(matches!(empty, RinError::BadRequest(_)));
+
+        let mut ctx = context_with_body("{not json");
+        let invalid = ctx.bind_json::<Vec<Item>>().await.unwrap_err();
+        assert!(matches!(invalid, RinError::BadRequest(_)));
+    }
+
+    #[test]
+    fn json_content_type_has_no_charset() {
+        let mut ctx = Context::test(Method::POST, "/");
+        ctx.json(&serde_json::json!({ "ok": true })).unwrap();
+
+        let response = ctx.response();
+        assert_eq!(
+            response.headers.get(header::CONTENT_TYPE).unwrap(),
+            "application/json"
+        );
+        assert_eq!(response.body, Bytes::from_static(br#"{"ok":true}"#));
+    }
+
+    #[derive(serde::Serialize)]
+    struct Greeting {
+        name: &'static str,
+    }
+
+    impl Display for Greeting {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Hello, <{}>", self.name)
+        }
+    }
+
+    #[test]
+    fn respond_negotiates_on_accept() {
+        let greeting = Greeting { name: "Rin" };
+
+        let mut ctx = Context::test(Method::POST, "/").with_header("Accept", "application/json");
+        ctx.respond(&greeting).unwrap();
+        assert_eq!(
+            ctx.response().headers[header::CONTENT_TYPE],
+            "application/json"
+        );
+        assert_eq!(
+            ctx.response().body,
+            Bytes::from_static(br#"{"name":"Rin"}"#)
+        );
+        assert_eq!(ctx.response().headers[header::VARY], "accept");
+
+        let mut ctx = Context::test(Method::POST, "/").with_header("Accept", "text/plain");
+        ctx.respond(&greeting).unwrap();
+        assert_eq!(
+            ctx.response().headers[header::CONTENT_TYPE],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(ctx.response().body, "Hello, <Rin>");
+
+        let mut ctx = Context::test(Method::POST, "/")
+            .with_header("Accept", "text/html, */*;q=0.1");
+        ctx.respond(&greeting).unwrap();
+        assert_eq!(ctx.response().body, "Hello, &lt;Rin&gt;");
     }
 }
@@ -1,13 +1,22 @@
+mod disposition;
+mod file;
 mod json;
+mod multipart;
 mod query;
+mod render;
 
-pub use json::ContextJsonExt;
+pub use disposition::ContentDisposition;
+pub use file::{ContextFileExt, DEFAULT_MAX_FILE_BYTES, FileConfig};
+pub use json::{ContextJsonExt, DEFAULT_MAX_JSON_BYTES, JsonConfig};
+pub use multipart::{
+    ContextMultipartExt, DEFAULT_MAX_TOTAL_UPLOAD_BYTES, DEFAULT_MAX_UPLOAD_BYTES, Field, Multipart,
+    MultipartConfig,
+};
 pub use query::ContextQueryExt;
+pub use render::{ContextRenderExt, TemplateEngine};
 
 #[cfg(test)]
 mod tests {
-    use super::*;
-
     #[test]
     fn it_works() {}
 }
@@ -0,0 +1,552 @@
+use crate::disposition::ContentDisposition;
+use async_trait::async_trait;
+use bytes::Bytes;
+use rin_core::header::{self, HeaderName};
+use rin_core::request::FileEntry;
+use rin_core::{ContentType, Context, HeaderMap, HeaderValue, RinError};
+use serde::de::DeserializeOwned;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::io::AsyncWriteExt;
+
+// 每次 `Field::chunk` 最多返回的字节数
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// `bind_multipart` 默认允许的单个上传文件大小：32 MiB。
+pub const DEFAULT_MAX_UPLOAD_BYTES: u64 = 32 * 1024 * 1024;
+
+/// `bind_multipart` 默认允许的上传文件总大小：128 MiB。
+pub const DEFAULT_MAX_TOTAL_UPLOAD_BYTES: u64 = 128 * 1024 * 1024;
+
+/// multipart 绑定配置。
+///
+/// 存入 `Context` 后（`ctx.set(MultipartConfig { .. })`）对 `bind_multipart` 生效；
+/// 未设置时使用 `MultipartConfig::default()`。限制的是写入临时文件的字节数。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MultipartConfig {
+    /// 单个上传文件允许的最大字节数。
+    pub max_file_bytes: u64,
+    /// 一个请求中所有上传文件合计允许的最大字节数。
+    pub max_total_file_bytes: u64,
+}
+
+impl Default for MultipartConfig {
+    fn default() -> Self {
+        MultipartConfig {
+            max_file_bytes: DEFAULT_MAX_UPLOAD_BYTES,
+            max_total_file_bytes: DEFAULT_MAX_TOTAL_UPLOAD_BYTES,
+        }
+    }
+}
+
+/// 扩展 `Context` 以提供按字段拉取的 multipart 解析。
+#[async_trait]
+pub trait ContextMultipartExt {
+    /// 创建 multipart 读取器，依次通过 `next_field` 获取每个字段。
+    ///
+    /// 与一次性解析到 `FormCache` 不同，字段按需解析，大文件不会被额外复制。
+    ///
+    /// # Errors
+    /// 请求不是 `multipart/form-data` 或缺少 `boundary` 参数时返回 `RinError::BadRequest`。
+    fn multipart(&mut self) -> Result<Multipart, RinError>;
+
+    /// 将 multipart 请求中的文本字段绑定到 `T`，文件字段写入临时文件。
+    ///
+    /// 文本字段按表单语义反序列化：数字、布尔等类型从字符串解析，重复字段可绑定到 `Vec`。
+    /// 绑定成功后所有字段存入 `Context::form()`，上传的文件可通过 `form().get_files(name)` 获取；
+    /// 临时文件不会自动删除，由处理函数负责移动或清理。
+    /// 返回错误时 `form()` 保持不变，本次已写入的临时文件都会被删除。
+    /// 文件名为空的文件字段（浏览器在未选择文件时发送）会被跳过。
+    ///
+    /// # Errors
+    /// 请求体不是合法的 multipart 或文本字段无法绑定到 `T` 时返回 `RinError::BadRequest`；
+    /// 上传文件超过 `MultipartConfig` 的限制时返回 `RinError::PayloadTooLarge`；
+    /// 写入临时文件失败时返回对应的 I/O 错误。
+    async fn bind_multipart<T: DeserializeOwned>(&mut self) -> Result<T, RinError>;
+}
+
+#[async_trait]
+impl ContextMultipartExt for Context {
+    fn multipart(&mut self) -> Result<Multipart, RinError> {
+        let boundary = self.headers().get(header::CONTENT_TYPE).and_then(parse_boundary);
+        let boundary = boundary.ok_or_else(|| {
+            RinError::BadRequest("Expected multipart/form-data with a boundary".to_string())
+        })?;
+        Ok(Multipart::new(self.body_bytes().clone(), boundary))
+    }
+
+    async fn bind_multipart<T: DeserializeOwned>(&mut self) -> Result<T, RinError> {
+        let config = self.get::<MultipartConfig>().copied().unwrap_or_default();
+        let mut multipart = self.multipart()?;
+        let mut pairs = Vec::new();
+        let mut files = Vec::new();
+        let mut temp_files = TempFiles(Vec::new());
+        let mut total_size = 0;
+        while let Some(mut field) = multipart.next_field().await? {
+            let name = field.name().to_string();
+            let Some(file_name) = field.file_name().map(str::to_string) else {
+                pairs.push((name, field.text().await?));
+                continue;
+            };
+            if file_name.is_empty() {
+                continue;
+            }
+
+            let mime_type = field
+                .content_type()
+                .unwrap_or("application/octet-stream")
+                .to_string();
+            let path = temp_upload_path();
+            // create_new 保证不会截断已存在的文件；创建成功后才登记，出错时只删除自己的文件
+            let mut file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+                .await?;
+            temp_files.0.push(path.clone());
+            let mut size = 0;
+            while let Some(chunk) = field.chunk().await {
+                size += chunk.len() as u64;
+                total_size += chunk.len() as u64;
+                if size > config.max_file_bytes || total_size > config.max_total_file_bytes {
+                    log::warn!(
+                        "Multipart upload {} exceeds the configured size limits",
+                        file_name
+                    );
+                    return Err(RinError::PayloadTooLarge);
+                }
+                file.write_all(&chunk).await?;
+            }
+            file.flush().await?;
+            files.push((name, FileEntry::new(file_name, path, size, mime_type)));
+        }
+
+        // 重新编码为 urlencoded 以复用表单的反序列化规则
+        let encoded = serde_html_form::to_string(&pairs)
+            .map_err(|e| RinError::Internal(format!("Failed to encode form fields: {}", e)))?;
+        let value = serde_html_form::from_str(&encoded).map_err(|e| {
+            log::warn!("Failed to bind multipart fields: {}", e);
+            RinError::BadRequest(format!("Invalid form fields: {}", e))
+        })?;
+
+        // 绑定成功，字段写入表单，临时文件交给处理函数
+        temp_files.0.clear();
+        for (name, text) in pairs {
+            self.form_mut().insert_field(name, text);
+        }
+        for (name, file) in files {
+            self.form_mut().insert_file(name, file);
+        }
+        Ok(value)
+    }
+}
+
+// 生成不重复的临时文件路径：进程号 + 时间戳 + 进程内计数器
+fn temp_upload_path() -> PathBuf {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_nanos());
+    std::env::temp_dir().join(format!(
+        "rin-upload-{}-{}-{}",
+        std::process::id(),
+        nanos,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ))
+}
+
+// 上传过程中创建的临时文件，drop 时删除；绑定成功后清空列表，文件归处理函数所有
+struct TempFiles(Vec<PathBuf>);
+
+impl Drop for TempFiles {
+    fn drop(&mut self) {
+        for path in &self.0 {
+            if let Err(e) = std::fs::remove_file(path) {
+                log::warn!("Failed to remove temporary upload {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
+/// multipart 请求体的读取器。
+#[derive(Debug)]
+pub struct Multipart {
+    body: Bytes,
+    delimiter: Vec<u8>,
+    // 下一个字段头部的起始位置；`None` 表示尚未找到第一个分隔符
+    pos: Option<usize>,
+    done: bool,
+}
+
+impl Multipart {
+    /// 使用请求体和分隔符（不含开头的 `--`）创建读取器。
+    pub fn new(body: Bytes, boundary: impl AsRef<str>) -> Self {
+        let delimiter = format!("--{}", boundary.as_ref()).into_bytes();
+        Multipart {
+            body,
+            delimiter,
+            pos: None,
+            done: false,
+        }
+    }
+
+    /// 读取下一个字段，所有字段读完后返回 `Ok(None)`。
+    ///
+    /// # Errors
+    /// 请求体格式错误（缺少分隔符、头部不完整、缺少字段名等）时返回 `RinError::BadRequest`。
+    pub async fn next_field(&mut self) -> Result<Option<Field>, RinError> {
+        if self.done {
+            return Ok(None);
+        }
+
+        // 跳过第一个分隔符之前的前导内容
+        let mut pos = match self.pos {
+            Some(pos) => pos,
+            None => {
+                let start = find(&self.body, &self.delimiter, 0)
+                    .ok_or_else(|| malformed("missing opening boundary"))?;
+                start + self.delimiter.len()
+            }
+        };
+
+        // 分隔符后紧跟 `--` 表示结束
+        if self.body[pos..].starts_with(b"--") {
+            self.done = true;
+            return Ok(None);
+        }
+        if !self.body[pos..].starts_with(b"\r\n") {
+            return Err(malformed("boundary must be followed by CRLF"));
+        }
+        pos += 2;
+
+        let headers_end = find(&self.body, b"\r\n\r\n", pos)
+            .ok_or_else(|| malformed("incomplete part headers"))?;
+        let headers = parse_part_headers(&self.body[pos..headers_end])?;
+        let data_start = headers_end + 4;
+
+        let mut closing = b"\r\n".to_vec();
+        closing.extend_from_slice(&self.delimiter);
+        let data_end = find(&self.body, &closing, data_start)
+            .ok_or_else(|| malformed("missing closing boundary"))?;
+        self.pos = Some(data_end + closing.len());
+
+        let disposition = headers
+            .get(header::CONTENT_DISPOSITION)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| malformed("part without Content-Disposition"))
+            .and_then(ContentDisposition::parse)?;
+        if disposition.name().is_none() {
+            return Err(malformed("part without a field name"));
+        }
+
+        Ok(Some(Field {
+            disposition,
+            headers,
+            data: self.body.slice(data_start..data_end),
+        }))
+    }
+}
+
+/// multipart 中的单个字段（普通文本字段或文件）。
+#[derive(Debug)]
+pub struct Field {
+    disposition: ContentDisposition,
+    headers: HeaderMap,
+    data: Bytes,
+}
+
+impl Field {
+    /// 字段名。
+    pub fn name(&self) -> &str {
+        self.disposition.name().unwrap_or_default()
+    }
+
+    /// 上传文件名；普通字段返回 `None`。
+    pub fn file_name(&self) -> Option<&str> {
+        self.disposition.filename()
+    }
+
+    /// 字段的 `Content-Type`。
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    /// 字段自身的头部。
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// 读取下一段数据，读完后返回 `None`。
+    pub async fn chunk(&mut self) -> Option<Bytes> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let len = self.data.len().min(CHUNK_SIZE);
+        Some(self.data.split_to(len))
+    }
+
+    /// 读取剩余的全部数据。
+    pub async fn bytes(self) -> Bytes {
+        self.data
+    }
+
+    /// 以 UTF-8 文本读取剩余的全部数据。
+    ///
+    /// # Errors
+    /// 数据不是合法 UTF-8 时返回 `RinError::BadRequest`。
+    pub async fn text(self) -> Result<String, RinError> {
+        String::from_utf8(self.data.to_vec())
+            .map_err(|_| RinError::BadRequest(format!("Field {} is not valid UTF-8", self.name())))
+    }
+}
+
+// 从 Content-Type 中取出 multipart/form-data 的 boundary 参数
+fn parse_boundary(content_type: &HeaderValue) -> Option<String> {
+    let content_type = ContentType::parse(content_type).ok()?;
+    if !content_type.is("multipart/form-data") {
+        return None;
+    }
+    content_type
+        .get_param("boundary")
+        .filter(|boundary| !boundary.is_empty())
+        .map(str::to_string)
+}
+
+fn parse_part_headers(raw: &[u8]) -> Result<HeaderMap, RinError> {
+    let mut headers = HeaderMap::new();
+    for line in raw.split(|&b| b == b'\n') {
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        if line.is_empty() {
+            continue;
+        }
+        let colon = line
+            .iter()
+            .position(|&b| b == b':')
+            .ok_or_else(|| malformed("invalid part header line"))?;
+        let name = HeaderName::from_bytes(&line[..colon])
+            .map_err(|_| malformed("invalid part header name"))?;
+        let value = HeaderValue::from_bytes(line[colon + 1..].trim_ascii())
+            .map_err(|_| malformed("invalid part header value"))?;
+        headers.append(name, value);
+    }
+    Ok(headers)
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    haystack
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|i| i + from)
+}
+
+fn malformed(reason: &str) -> RinError {
+    RinError::BadRequest(format!("Malformed multipart body: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rin_core::Method;
+    use std::collections::HashMap;
+
+    const BODY: &str = "preamble\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"title\"\r\n\
+        \r\n\
+        Hello Rin\r\n\
+        --XyZ\r\n\
+        Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\
+        Content-Type: text/plain\r\n\
+        \r\n\
+        line one\r\nline two\r\n\
+        --XyZ--\r\n";
+
+    fn context(content_type: &str, body: &'static str) -> Context {
+        Context::test(Method::POST, "/upload")
+            .with_header("Content-Type", content_type)
+            .with_body(body)
+    }
+
+    #[tokio::test]
+    async fn iterates_fields_and_reads_their_bytes() {
+        let mut ctx = context("multipart/form-data; boundary=\"XyZ\"", BODY);
+        let mut multipart = ctx.multipart().unwrap();
+
+        let title = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(title.name(), "title");
+        assert_eq!(title.file_name(), None);
+        assert_eq!(title.text().await.unwrap(), "Hello Rin");
+
+        let mut upload = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(upload.name(), "upload");
+        assert_eq!(upload.file_name(), Some("a.txt"));
+        assert_eq!(upload.content_type(), Some("text/plain"));
+        let mut data = Vec::new();
+        while let Some(chunk) = upload.chunk().await {
+            data.extend_from_slice(&chunk);
+        }
+        assert_eq!(data, b"line one\r\nline two");
+
+        assert!(multipart.next_field().await.unwrap().is_none());
+        assert!(multipart.next_field().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn large_fields_are_chunked() {
+        let payload = "x".repeat(CHUNK_SIZE + 10);
+        let body = format!(
+            "--b\r\nContent-Disposition: form-data; name=\"big\"\r\n\r\n{}\r\n--b--\r\n",
+            payload
+        );
+        let mut multipart = Multipart::new(Bytes::from(body), "b");
+        let mut field = multipart.next_field().await.unwrap().unwrap();
+        assert_eq!(field.chunk().await.unwrap().len(), CHUNK_SIZE);
+        assert_eq!(field.chunk().await.unwrap().len(), 10);
+        assert!(field.chunk().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_missing_boundary_and_malformed_bodies() {
+        let mut ctx = context("application/json", "{}");
+        assert!(matches!(ctx.multipart(), Err(RinError::BadRequest(_))));
+
+        let mut ctx = context(
+            "multipart/form-data; boundary=XyZ",
+            "--XyZ\r\nno headers end",
+        );
+        let mut multipart = ctx.multipart().unwrap();
+        assert!(multipart.next_field().await.is_err());
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Upload {
+        title: String,
+        tags: Vec<String>,
+        count: u32,
+    }
+
+    #[tokio::test]
+    async fn bind_multipart_binds_text_fields_and_keeps_files() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\nHello & Rin\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"tags\"\r\n\r\na\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"tags\"\r\n\r\nb\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"count\"\r\n\r\n3\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\
+            Content-Type: text/plain\r\n\r\nfile body\r\n\
+            --XyZ--\r\n";
+        let mut ctx = context("multipart/form-data; boundary=XyZ", body);
+
+        let upload: Upload = ctx.bind_multipart().await.unwrap();
+        assert_eq!(upload.title, "Hello & Rin");
+        assert_eq!(upload.tags, ["a", "b"]);
+        assert_eq!(upload.count, 3);
+        assert_eq!(ctx.post_form("title"), Some("Hello & Rin"));
+
+        let files = ctx.form().get_files("upload").unwrap();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].filename(), "a.txt");
+        assert_eq!(files[0].mime_type(), "text/plain");
+        assert_eq!(files[0].size(), 9);
+        assert_eq!(std::fs::read(files[0].path()).unwrap(), b"file body");
+        std::fs::remove_file(files[0].path()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn bind_multipart_enforces_size_limits() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"a\"; filename=\"a.txt\"\r\n\r\n12345\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"b\"; filename=\"b.txt\"\r\n\r\n12345\r\n\
+            --XyZ--\r\n";
+
+        let mut ctx = context("multipart/form-data; boundary=XyZ", body);
+        ctx.set(MultipartConfig {
+            max_file_bytes: 4,
+            ..MultipartConfig::default()
+        });
+        let err = ctx.bind_multipart::<HashMap<String, String>>().await.unwrap_err();
+        assert!(matches!(err, RinError::PayloadTooLarge));
+        assert!(ctx.form().get_files("a").is_none());
+
+        let mut ctx = context("multipart/form-data; boundary=XyZ", body);
+        ctx.set(MultipartConfig {
+            max_file_bytes: 5,
+            max_total_file_bytes: 9,
+        });
+        let err = ctx.bind_multipart::<HashMap<String, String>>().await.unwrap_err();
+        assert!(matches!(err, RinError::PayloadTooLarge));
+        assert!(ctx.form().get_files("a").is_none());
+    }
+
+    #[tokio::test]
+    async fn bind_multipart_does_not_keep_files_when_binding_fails() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"count\"\r\n\r\nnot a number\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\r\nbody\r\n\
+            --XyZ--\r\n";
+        let mut ctx = context("multipart/form-data; boundary=XyZ", body);
+        let err = ctx.bind_multipart::<Upload>().await.unwrap_err();
+        assert!(matches!(err, RinError::BadRequest(_)));
+        assert!(ctx.form().get_files("upload").is_none());
+        assert_eq!(ctx.post_form("count"), None);
+    }
+
+    #[tokio::test]
+    async fn bind_multipart_keeps_form_untouched_on_size_errors() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"upload\"; filename=\"a.txt\"\r\n\r\n12345\r\n\
+            --XyZ--\r\n";
+        let mut ctx = context("multipart/form-data; boundary=XyZ", body);
+        ctx.set(MultipartConfig {
+            max_file_bytes: 4,
+            ..MultipartConfig::default()
+        });
+        let err = ctx.bind_multipart::<HashMap<String, String>>().await.unwrap_err();
+        assert!(matches!(err, RinError::PayloadTooLarge));
+        assert_eq!(ctx.post_form("title"), None);
+    }
+
+    #[tokio::test]
+    async fn bind_multipart_skips_empty_file_parts() {
+        let body = "--XyZ\r\n\
+            Content-Disposition: form-data; name=\"title\"\r\n\r\nHello\r\n\
+            --XyZ\r\n\
+            Content-Disposition: form-data; name=\"upload\"; filename=\"\"\r\n\
+            Content-Type: application/octet-stream\r\n\r\n\r\n\
+            --XyZ--\r\n";
+        let mut ctx = context("multipart/form-data; boundary=XyZ", body);
+        let fields: HashMap<String, String> = ctx.bind_multipart().await.unwrap();
+        assert_eq!(fields.len(), 1);
+        assert_eq!(fields["title"], "Hello");
+        assert!(ctx.form().get_files("upload").is_none());
+        assert_eq!(ctx.post_form("upload"), None);
+    }
+
+    #[test]
+    fn temp_files_are_removed_on_drop() {
+        let path = temp_upload_path();
+        std::fs::write(&path, b"partial").unwrap();
+        drop(TempFiles(vec![path.clone()]));
+        assert!(!path.exists());
+
+        let path = temp_upload_path();
+        std::fs::write(&path, b"kept").unwrap();
+        let mut temp_files = TempFiles(vec![path.clone()]);
+        temp_files.0.clear();
+        drop(temp_files);
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+}
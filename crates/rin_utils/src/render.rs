@@ -0,0 +1,87 @@
+use async_trait::async_trait;
+use rin_core::response::utf8_content_type;
+use rin_core::{Context, RinError, header};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// 可插拔的模板引擎。
+///
+/// 框架本身不提供模板实现，只负责在 `Context::render` 中调用它并设置 HTML 响应。
+pub trait TemplateEngine: Send + Sync + 'static {
+    /// 使用 `data` 渲染名为 `name` 的模板，返回渲染后的 HTML。
+    fn render(&self, name: &str, data: &Value) -> Result<String, RinError>;
+}
+
+/// 扩展 `Context` 以提供模板渲染方法。
+#[async_trait]
+pub trait ContextRenderExt {
+    /// 将模板引擎存入上下文，通常由服务器或中间件在请求开始时注入。
+    fn set_template_engine(&mut self, engine: Arc<dyn TemplateEngine>);
+
+    /// 渲染模板并将结果设置为 `text/html` 响应。
+    ///
+    /// # Errors
+    /// 如果上下文中没有模板引擎，则返回 `RinError::Internal`；
+    /// 渲染失败时返回模板引擎给出的错误。
+    fn render(&mut self, name: &str, data: &Value) -> Result<(), RinError>;
+}
+
+#[async_trait]
+impl ContextRenderExt for Context {
+    fn set_template_engine(&mut self, engine: Arc<dyn TemplateEngine>) {
+        self.set(engine);
+    }
+
+    fn render(&mut self, name: &str, data: &Value) -> Result<(), RinError> {
+        let engine = self
+            .get::<Arc<dyn TemplateEngine>>()
+            .cloned()
+            .ok_or_else(|| RinError::Internal("No template engine configured".to_string()))?;
+        let html = engine.render(name, data)?;
+
+        self.headers_mut()
+            .insert(header::CONTENT_TYPE, utf8_content_type("text/html"));
+        self.set_body(html);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rin_core::{Bytes, Method};
+
+    struct StubEngine;
+
+    impl TemplateEngine for StubEngine {
+        fn render(&self, name: &str, data: &Value) -> Result<String, RinError> {
+            Ok(format!(
+                "<h1>{}: {}</h1>",
+                name,
+                data["title"].as_str().unwrap_or_default()
+            ))
+        }
+    }
+
+    #[test]
+    fn render_uses_engine_and_sets_html() {
+        let mut ctx = Context::test(Method::GET, "/");
+        ctx.set_template_engine(Arc::new(StubEngine));
+        ctx.render("index", &serde_json::json!({ "title": "Rin" }))
+            .unwrap();
+
+        let response = ctx.response();
+        assert_eq!(
+            response.headers.get(header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8"
+        );
+        assert_eq!(response.body, Bytes::from_static(b"<h1>index: Rin</h1>"));
+    }
+
+    #[test]
+    fn render_without_engine_is_internal_error() {
+        let mut ctx = Context::test(Method::GET, "/");
+        let err = ctx.render("index", &Value::Null).unwrap_err();
+        assert!(matches!(err, RinError::Internal(_)));
+    }
+}
@@ -0,0 +1,438 @@
+use bytes::{Buf, BufMut, Bytes, BytesMut};
+use thiserror::Error as ThisError;
+
+/// RFC 6455 帧操作码。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl Opcode {
+    fn from_u8(byte: u8) -> Result<Self, WsError> {
+        match byte {
+            0x0 => Ok(Opcode::Continuation),
+            0x1 => Ok(Opcode::Text),
+            0x2 => Ok(Opcode::Binary),
+            0x8 => Ok(Opcode::Close),
+            0x9 => Ok(Opcode::Ping),
+            0xA => Ok(Opcode::Pong),
+            other => Err(WsError::UnknownOpcode(other)),
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Opcode::Continuation => 0x0,
+            Opcode::Text => 0x1,
+            Opcode::Binary => 0x2,
+            Opcode::Close => 0x8,
+            Opcode::Ping => 0x9,
+            Opcode::Pong => 0xA,
+        }
+    }
+
+    /// 是否为控制帧（Close、Ping、Pong）。
+    pub fn is_control(self) -> bool {
+        matches!(self, Opcode::Close | Opcode::Ping | Opcode::Pong)
+    }
+}
+
+/// `Decoder` 默认允许的最大消息长度：16 MiB。
+pub const DEFAULT_MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// WebSocket 帧编解码错误。出现任何错误后都应以 `close_code` 给出的状态码关闭连接。
+#[derive(Debug, ThisError, PartialEq, Eq)]
+pub enum WsError {
+    #[error("Unknown opcode: {0:#x}")]
+    UnknownOpcode(u8),
+
+    #[error("Reserved bits set without a negotiated extension")]
+    ReservedBitsSet,
+
+    #[error("Client frames must be masked")]
+    UnmaskedFrame,
+
+    #[error("Control frames must not be fragmented or exceed 125 bytes")]
+    InvalidControlFrame,
+
+    #[error("Unexpected continuation frame")]
+    UnexpectedContinuation,
+
+    #[error("New data frame while a fragmented message is in progress")]
+    UnfinishedMessage,
+
+    #[error("Payload length is too large")]
+    PayloadTooLarge,
+
+    #[error("Text message is not valid UTF-8")]
+    InvalidUtf8,
+
+    #[error("Close frame has an invalid length or status code")]
+    InvalidCloseFrame,
+}
+
+impl WsError {
+    /// 关闭连接时应使用的状态码（RFC 6455 7.4.1）。
+    pub fn close_code(&self) -> u16 {
+        match self {
+            WsError::PayloadTooLarge => 1009,
+            WsError::InvalidUtf8 => 1007,
+            _ => 1002,
+        }
+    }
+}
+
+/// 服务端的 WebSocket 消息解码器。
+///
+/// 持续向其中追加从连接读取的字节，再反复调用 `next_message` 取出完整消息。
+/// 分片消息会被重新拼接；夹在分片之间的控制帧会立即返回。
+/// 按 RFC 6455，来自客户端的帧必须带掩码，否则返回 `WsError::UnmaskedFrame`。
+/// 单条消息（含所有分片）超过上限时返回 `WsError::PayloadTooLarge`，
+/// 在读到负载之前就会根据帧头声明的长度拒绝，不会先缓存数据。
+#[derive(Debug)]
+pub struct Decoder {
+    buf: BytesMut,
+    fragments: Option<(Opcode, BytesMut)>,
+    max_message_bytes: usize,
+}
+
+impl Default for Decoder {
+    fn default() -> Self {
+        Self::with_max_message_bytes(DEFAULT_MAX_MESSAGE_BYTES)
+    }
+}
+
+impl Decoder {
+    /// 创建一个空的解码器，消息长度上限为 `DEFAULT_MAX_MESSAGE_BYTES`。
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建一个空的解码器，并指定单条消息允许的最大字节数。
+    pub fn with_max_message_bytes(max_message_bytes: usize) -> Self {
+        Decoder {
+            buf: BytesMut::new(),
+            fragments: None,
+            max_message_bytes,
+        }
+    }
+
+    /// 追加从连接读取到的字节。
+    pub fn feed(&mut self, data: &[u8]) {
+        self.buf.extend_from_slice(data);
+    }
+
+    /// 取出下一条完整消息；数据不足时返回 `Ok(None)`，不会消费任何字节。
+    pub fn next_message(&mut self) -> Result<Option<(Opcode, Bytes)>, WsError> {
+        loop {
+            let (fin, opcode, payload) = match self.next_frame()? {
+                Some(frame) => frame,
+                None => return Ok(None),
+            };
+
+            if opcode == Opcode::Close {
+                validate_close(&payload)?;
+            }
+            if opcode.is_control() {
+                return Ok(Some((opcode, payload)));
+            }
+
+            match (opcode, self.fragments.as_mut()) {
+                (Opcode::Continuation, None) => return Err(WsError::UnexpectedContinuation),
+                (Opcode::Continuation, Some((_, data))) => data.extend_from_slice(&payload),
+                (_, Some(_)) => return Err(WsError::UnfinishedMessage),
+                (_, None) if fin => return finish(opcode, payload),
+                (_, None) => self.fragments = Some((opcode, BytesMut::from(&payload[..]))),
+            }
+
+            if fin {
+                let (opcode, data) = self.fragments.take().expect("分片消息应当存在");
+                return finish(opcode, data.freeze());
+            }
+        }
+    }
+
+    // 解析缓冲区开头的单个帧，返回 (FIN, 操作码, 去掩码后的负载)
+    fn next_frame(&mut self) -> Result<Option<(bool, Opcode, Bytes)>, WsError> {
+        let buf = &self.buf[..];
+        if buf.len() < 2 {
+            return Ok(None);
+        }
+
+        let fin = buf[0] & 0x80 != 0;
+        if buf[0] & 0x70 != 0 {
+            return Err(WsError::ReservedBitsSet);
+        }
+        let opcode = Opcode::from_u8(buf[0] & 0x0F)?;
+        if buf[1] & 0x80 == 0 {
+            return Err(WsError::UnmaskedFrame);
+        }
+
+        let (len, mut header_len) = match buf[1] & 0x7F {
+            126 if buf.len() >= 4 => (u64::from(u16::from_be_bytes([buf[2], buf[3]])), 4),
+            127 if buf.len() >= 10 => {
+                let len = u64::from_be_bytes(buf[2..10].try_into().expect("长度为 8 字节"));
+                if len >> 63 != 0 {
+                    return Err(WsError::PayloadTooLarge);
+                }
+                (len, 10)
+            }
+            126 | 127 => return Ok(None),
+            len => (u64::from(len), 2),
+        };
+        if opcode.is_control() && (!fin || len > 125) {
+            return Err(WsError::InvalidControlFrame);
+        }
+        let len = usize::try_from(len).map_err(|_| WsError::PayloadTooLarge)?;
+        let buffered = match (opcode, &self.fragments) {
+            (Opcode::Continuation, Some((_, data))) => data.len(),
+            _ => 0,
+        };
+        if buffered.saturating_add(len) > self.max_message_bytes {
+            return Err(WsError::PayloadTooLarge);
+        }
+
+        if buf.len() < header_len + 4 {
+            return Ok(None);
+        }
+        let mask: [u8; 4] = buf[header_len..header_len + 4]
+            .try_into()
+            .expect("掩码为 4 字节");
+        header_len += 4;
+        if buf.len() - header_len < len {
+            return Ok(None);
+        }
+
+        self.buf.advance(header_len);
+        let mut payload = self.buf.split_to(len);
+        apply_mask(&mut payload, mask);
+        Ok(Some((fin, opcode, payload.freeze())))
+    }
+}
+
+// Close 帧负载为空，或以合法的 2 字节状态码开头、后接 UTF-8 原因
+fn validate_close(payload: &[u8]) -> Result<(), WsError> {
+    match payload {
+        [] => Ok(()),
+        [hi, lo, reason @ ..] => {
+            let code = u16::from_be_bytes([*hi, *lo]);
+            // 1004-1006、1015 为保留值，不能出现在帧中；1016-2999 尚未分配
+            if !matches!(code, 1000..=1003 | 1007..=1014 | 3000..=4999) {
+                return Err(WsError::InvalidCloseFrame);
+            }
+            std::str::from_utf8(reason).map_err(|_| WsError::InvalidUtf8)?;
+            Ok(())
+        }
+        [_] => Err(WsError::InvalidCloseFrame),
+    }
+}
+
+fn finish(opcode: Opcode, payload: Bytes) -> Result<Option<(Opcode, Bytes)>, WsError> {
+    if opcode == Opcode::Text && std::str::from_utf8(&payload).is_err() {
+        return Err(WsError::InvalidUtf8);
+    }
+    Ok(Some((opcode, payload)))
+}
+
+/// 解码 `buf` 开头的第一条完整消息，适合一次性处理整段数据。
+///
+/// 持续读取连接时应使用 `Decoder`，以保留尚未消费的字节和分片状态。
+pub fn decode_frame(buf: &[u8]) -> Result<Option<(Opcode, Bytes)>, WsError> {
+    let mut decoder = Decoder::new();
+    decoder.feed(buf);
+    decoder.next_message()
+}
+
+/// 编码服务端发往客户端的单个完整帧（FIN 置位、不带掩码）。
+pub fn encode_frame(opcode: Opcode, payload: &[u8]) -> Bytes {
+    encode(opcode, payload, None)
+}
+
+/// 编码带掩码的单个完整帧，即客户端发往服务端的格式。
+pub fn encode_masked_frame(opcode: Opcode, payload: &[u8], mask: [u8; 4]) -> Bytes {
+    encode(opcode, payload, Some(mask))
+}
+
+fn encode(opcode: Opcode, payload: &[u8], mask: Option<[u8; 4]>) -> Bytes {
+    let mut frame = BytesMut::with_capacity(payload.len() + 14);
+    frame.put_u8(0x80 | opcode.as_u8());
+
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        len @ 0..=125 => frame.put_u8(mask_bit | len as u8),
+        len @ 126..=0xFFFF => {
+            frame.put_u8(mask_bit | 126);
+            frame.put_u16(len as u16);
+        }
+        len => {
+            frame.put_u8(mask_bit | 127);
+            frame.put_u64(len as u64);
+        }
+    }
+
+    match mask {
+        Some(mask) => {
+            frame.put_slice(&mask);
+            let start = frame.len();
+            frame.put_slice(payload);
+            apply_mask(&mut frame[start..], mask);
+        }
+        None => frame.put_slice(payload),
+    }
+    frame.freeze()
+}
+
+fn apply_mask(data: &mut [u8], mask: [u8; 4]) {
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MASK: [u8; 4] = [0x37, 0xfa, 0x21, 0x3d];
+
+    // RFC 6455 5.7 中的示例帧
+    const MASKED_HELLO: [u8; 11] = [
+        0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+    ];
+    const UNMASKED_HELLO: [u8; 7] = [0x81, 0x05, 0x48, 0x65, 0x6c, 0x6c, 0x6f];
+    const MASKED_PONG: [u8; 11] = [
+        0x8a, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+    ];
+
+    #[test]
+    fn decodes_rfc_masked_text_frame() {
+        let message = decode_frame(&MASKED_HELLO).unwrap();
+        assert_eq!(message, Some((Opcode::Text, Bytes::from_static(b"Hello"))));
+
+        let message = decode_frame(&MASKED_PONG).unwrap();
+        assert_eq!(message, Some((Opcode::Pong, Bytes::from_static(b"Hello"))));
+    }
+
+    #[test]
+    fn encodes_rfc_frames() {
+        assert_eq!(encode_frame(Opcode::Text, b"Hello"), &UNMASKED_HELLO[..]);
+        assert_eq!(
+            encode_masked_frame(Opcode::Text, b"Hello", MASK),
+            &MASKED_HELLO[..]
+        );
+
+        let frame = encode_frame(Opcode::Binary, &[0; 256]);
+        assert_eq!(frame[..4], [0x82, 0x7E, 0x01, 0x00]);
+        let frame = encode_frame(Opcode::Binary, &vec![0; 65536]);
+        assert_eq!(frame[..10], [0x82, 0x7F, 0, 0, 0, 0, 0, 1, 0, 0]);
+    }
+
+    #[test]
+    fn rejects_unmasked_client_frames() {
+        assert_eq!(decode_frame(&UNMASKED_HELLO), Err(WsError::UnmaskedFrame));
+    }
+
+    #[test]
+    fn reassembles_fragments_around_control_frames() {
+        let mut first = encode_masked_frame(Opcode::Text, b"Hel", MASK).to_vec();
+        first[0] &= 0x7F; // 清除 FIN
+        let mut last = encode_masked_frame(Opcode::Continuation, b"lo", MASK).to_vec();
+        last[0] = 0x80;
+        let ping = encode_masked_frame(Opcode::Ping, b"hi", MASK);
+
+        let mut decoder = Decoder::new();
+        decoder.feed(&first);
+        assert_eq!(decoder.next_message().unwrap(), None);
+        decoder.feed(&ping);
+        decoder.feed(&last[..3]);
+        assert_eq!(
+            decoder.next_message().unwrap(),
+            Some((Opcode::Ping, Bytes::from_static(b"hi")))
+        );
+        assert_eq!(decoder.next_message().unwrap(), None);
+        decoder.feed(&last[3..]);
+        assert_eq!(
+            decoder.next_message().unwrap(),
+            Some((Opcode::Text, Bytes::from_static(b"Hello")))
+        );
+    }
+
+    #[test]
+    fn rejects_protocol_violations() {
+        let mut fragmented_ping = encode_masked_frame(Opcode::Ping, b"x", MASK).to_vec();
+        fragmented_ping[0] &= 0x7F;
+        assert_eq!(
+            decode_frame(&fragmented_ping),
+            Err(WsError::InvalidControlFrame)
+        );
+
+        let big_close = encode_masked_frame(Opcode::Close, &[0; 126], MASK);
+        assert_eq!(decode_frame(&big_close), Err(WsError::InvalidControlFrame));
+
+        let continuation = encode_masked_frame(Opcode::Continuation, b"x", MASK);
+        assert_eq!(
+            decode_frame(&continuation),
+            Err(WsError::UnexpectedContinuation)
+        );
+
+        let invalid_text = encode_masked_frame(Opcode::Text, &[0xff], MASK);
+        assert_eq!(decode_frame(&invalid_text), Err(WsError::InvalidUtf8));
+
+        assert_eq!(
+            decode_frame(&[0x83, 0x80, 0, 0, 0, 0]),
+            Err(WsError::UnknownOpcode(3))
+        );
+        assert_eq!(
+            decode_frame(&[0xC1, 0x80, 0, 0, 0, 0]),
+            Err(WsError::ReservedBitsSet)
+        );
+    }
+
+    #[test]
+    fn enforces_max_message_bytes() {
+        let frame = encode_masked_frame(Opcode::Binary, &[0; 9], MASK);
+        let mut decoder = Decoder::with_max_message_bytes(8);
+        // 仅凭帧头即可拒绝
+        decoder.feed(&frame[..2]);
+        assert_eq!(decoder.next_message(), Err(WsError::PayloadTooLarge));
+
+        let mut first = encode_masked_frame(Opcode::Binary, &[0; 5], MASK).to_vec();
+        first[0] &= 0x7F;
+        let mut last = encode_masked_frame(Opcode::Continuation, &[0; 4], MASK).to_vec();
+        last[0] = 0x80;
+        let mut decoder = Decoder::with_max_message_bytes(8);
+        decoder.feed(&first);
+        decoder.feed(&last);
+        assert_eq!(decoder.next_message(), Err(WsError::PayloadTooLarge));
+        assert_eq!(WsError::PayloadTooLarge.close_code(), 1009);
+
+        let mut decoder = Decoder::with_max_message_bytes(8);
+        decoder.feed(&encode_masked_frame(Opcode::Binary, &[0; 8], MASK));
+        assert!(decoder.next_message().unwrap().is_some());
+    }
+
+    #[test]
+    fn validates_close_payloads() {
+        let close =
+            |payload: &[u8]| decode_frame(&encode_masked_frame(Opcode::Close, payload, MASK));
+
+        assert!(close(b"").unwrap().is_some());
+        assert!(close(b"\x03\xe8bye").unwrap().is_some());
+        assert!(close(&4999u16.to_be_bytes()).unwrap().is_some());
+
+        assert_eq!(close(b"\x03"), Err(WsError::InvalidCloseFrame));
+        for code in [0u16, 999, 1004, 1005, 1006, 1015, 2000, 5000] {
+            assert_eq!(
+                close(&code.to_be_bytes()),
+                Err(WsError::InvalidCloseFrame),
+                "{}",
+                code
+            );
+        }
+        assert_eq!(close(b"\x03\xe8\xff"), Err(WsError::InvalidUtf8));
+    }
+}
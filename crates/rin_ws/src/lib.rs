@@ -0,0 +1,6 @@
+mod codec;
+
+pub use codec::{
+    DEFAULT_MAX_MESSAGE_BYTES, Decoder, Opcode, WsError, decode_frame, encode_frame,
+    encode_masked_frame,
+};